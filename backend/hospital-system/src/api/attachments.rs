@@ -0,0 +1,126 @@
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+
+use crate::attachments::{save_multipart_field, AttachmentConfig};
+use crate::database;
+use crate::middleware::{GuardedData, ReadPatients, WritePatients};
+use crate::utils::Error;
+
+/// POST /patients/{id}/attachments - Upload a scanned record or image for a
+/// patient.
+///
+/// The multipart body is streamed to disk chunk-by-chunk rather than
+/// buffered in memory - see [`crate::attachments::save_multipart_field`] -
+/// and rejected mid-stream if it exceeds `ATTACHMENT_MAX_SIZE_BYTES` or
+/// isn't one of `ATTACHMENT_ALLOWED_CONTENT_TYPES`. Only the first field in
+/// the body is stored, whether or not it carries a filename; a request with
+/// no field at all is a validation error.
+async fn upload_attachment(
+    pool: web::Data<PgPool>,
+    attachment_config: web::Data<AttachmentConfig>,
+    patient_id: web::Path<i32>,
+    mut payload: Multipart,
+    _scope: GuardedData<WritePatients>,
+) -> Result<HttpResponse, Error> {
+    let patient_id = patient_id.into_inner();
+
+    database::get_patient_by_id(&pool, patient_id)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("patient {}", patient_id)))?;
+
+    let field = payload
+        .try_next()
+        .await
+        .map_err(|e| Error::Validation(format!("failed to read multipart body: {}", e)))?
+        .ok_or_else(|| Error::Validation("no file field present in upload".to_string()))?;
+
+    let stored = save_multipart_field(
+        field,
+        &attachment_config.storage_dir,
+        attachment_config.max_size_bytes,
+        &attachment_config.allowed_content_types,
+    )
+    .await?;
+
+    let attachment = database::create_attachment(
+        &pool,
+        patient_id,
+        &stored.filename,
+        &stored.content_type,
+        stored.size_bytes,
+        &stored.sha256,
+        &stored.storage_path,
+    )
+    .await?;
+
+    log::info!(
+        "Stored attachment {} ({} bytes, {}) for patient {}",
+        attachment.id,
+        attachment.size_bytes,
+        attachment.content_type,
+        patient_id
+    );
+    Ok(HttpResponse::Created().json(attachment))
+}
+
+/// GET /patients/{id}/attachments - List attachment metadata for a patient.
+async fn list_attachments(
+    pool: web::Data<PgPool>,
+    patient_id: web::Path<i32>,
+    _scope: GuardedData<ReadPatients>,
+) -> Result<HttpResponse, Error> {
+    let patient_id = patient_id.into_inner();
+
+    database::get_patient_by_id(&pool, patient_id)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("patient {}", patient_id)))?;
+
+    let attachments = database::get_attachments_by_patient(&pool, patient_id).await?;
+    Ok(HttpResponse::Ok().json(attachments))
+}
+
+/// GET /attachments/{id} - Download a stored attachment.
+async fn download_attachment(
+    pool: web::Data<PgPool>,
+    id: web::Path<i32>,
+    _scope: GuardedData<ReadPatients>,
+) -> Result<HttpResponse, Error> {
+    let attachment_id = id.into_inner();
+
+    let attachment = database::get_attachment_by_id(&pool, attachment_id)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("attachment {}", attachment_id)))?;
+
+    let bytes = tokio::fs::read(&attachment.storage_path).await.map_err(|e| {
+        log::error!(
+            "Failed to read attachment {} from '{}': {}",
+            attachment_id,
+            attachment.storage_path,
+            e
+        );
+        Error::ServiceUnavailable("attachment storage is unavailable".to_string())
+    })?;
+
+    log::info!("Downloaded attachment {} for patient {}", attachment_id, attachment.patient_id);
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type.clone())
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", attachment.filename),
+        ))
+        .body(bytes))
+}
+
+/// Configure attachment routes. Mounted alongside `/patients` - `{id}` in
+/// `/attachments/{id}` is the attachment's own ID, not a patient ID, so it
+/// gets its own top-level scope rather than nesting under `/patients`.
+pub fn configure_attachments(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/patients/{id}/attachments")
+            .route("", web::post().to(upload_attachment))
+            .route("", web::get().to(list_attachments)),
+    )
+    .service(web::scope("/attachments").route("/{id}", web::get().to(download_attachment)));
+}
@@ -0,0 +1,263 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use serde::{Deserialize, Serialize};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use crate::database;
+use crate::middleware::jwt_auth::Claims;
+use crate::middleware::user_session::UserClaims;
+use crate::models::User;
+use crate::utils::Error;
+use crate::config::{JwtSecret, JwtAudience};
+
+/// How long a user session JWT is valid before `/auth/refresh` is needed.
+const SESSION_TTL_SECS: i64 = 900;
+
+/// How long past `exp` a presented access token may still be exchanged for
+/// a fresh session via `/auth/refresh`. `validate_exp` is deliberately off
+/// in [`refresh`] so a token that expired moments ago (e.g. a request in flight when
+/// it lapsed) can still refresh, but without this bound a token that expired
+/// an hour, a week, or a year ago would refresh just as well - a leaked
+/// access token would never truly die.
+const REFRESH_GRACE_PERIOD_SECS: i64 = 300;
+
+/// Request body for `/auth/token`
+#[derive(Deserialize)]
+struct TokenRequest {
+    client_id: String,
+    client_secret: String,
+}
+
+#[derive(Serialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: &'static str,
+    expires_in: i64,
+}
+
+/// A known service identity allowed to mint tokens, e.g. the police system
+struct ServiceIdentity {
+    client_id: &'static str,
+    client_secret_env: &'static str,
+    role: &'static str,
+    scope: &'static str,
+}
+
+const KNOWN_SERVICES: &[ServiceIdentity] = &[
+    ServiceIdentity {
+        client_id: "police-system",
+        client_secret_env: "POLICE_SYSTEM_CLIENT_SECRET",
+        role: "service",
+        scope: "read:patients",
+    },
+];
+
+/// POST /auth/token - Exchange a client id/secret pair for a short-lived JWT
+///
+/// This lets the hospital's known consumers (currently the police system)
+/// authenticate programmatically instead of sharing the static `API_KEY`.
+/// Tokens are minted with a one hour lifetime and the scopes configured for
+/// that client identity in [`KNOWN_SERVICES`].
+async fn issue_token(
+    body: web::Json<TokenRequest>,
+    jwt_secret: web::Data<JwtSecret>,
+    jwt_audience: web::Data<JwtAudience>,
+) -> Result<HttpResponse, Error> {
+    let request = body.into_inner();
+
+    let identity = KNOWN_SERVICES
+        .iter()
+        .find(|s| s.client_id == request.client_id);
+
+    let identity = match identity {
+        Some(identity) => identity,
+        None => {
+            log::warn!("Token request for unknown client_id '{}'", request.client_id);
+            return Err(Error::Unauthorized);
+        }
+    };
+
+    let expected_secret = match std::env::var(identity.client_secret_env) {
+        Ok(secret) => secret,
+        Err(_) => {
+            log::error!("{} is not configured", identity.client_secret_env);
+            return Err(Error::Unauthorized);
+        }
+    };
+
+    if !constant_time_eq::constant_time_eq(request.client_secret.as_bytes(), expected_secret.as_bytes()) {
+        log::warn!("Invalid client_secret for client_id '{}'", request.client_id);
+        return Err(Error::Unauthorized);
+    }
+
+    let expires_in = 3600i64;
+    let now = chrono::Utc::now();
+    let claims = Claims {
+        sub: identity.client_id.to_string(),
+        role: identity.role.to_string(),
+        scope: identity.scope.to_string(),
+        exp: (now.timestamp() + expires_in) as usize,
+        nbf: now.timestamp() as usize,
+        aud: jwt_audience.0.clone(),
+    };
+
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(jwt_secret.0.as_bytes()))
+        .map_err(|e| {
+            log::error!("Failed to encode JWT for client_id '{}': {}", identity.client_id, e);
+            Error::ServiceUnavailable("token issuance failed".to_string())
+        })?;
+
+    log::info!("Issued access token for client_id '{}'", identity.client_id);
+    Ok(HttpResponse::Ok().json(TokenResponse {
+        access_token: token,
+        token_type: "Bearer",
+        expires_in,
+    }))
+}
+
+/// Request body for `/auth/login`
+#[derive(Deserialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+/// Request body for `/auth/refresh`
+#[derive(Deserialize)]
+struct RefreshRequest {
+    access_token: String,
+}
+
+#[derive(Serialize)]
+struct SessionResponse {
+    access_token: String,
+    token_type: &'static str,
+    expires_in: i64,
+}
+
+/// POST /auth/login - Exchange a username/password for a short-lived user
+/// session JWT
+///
+/// The stored `password_hash` is a full PHC string (algorithm, params, salt
+/// and hash together), so verification never needs to know which Argon2
+/// parameters were used to create it.
+async fn login(
+    pool: web::Data<PgPool>,
+    body: web::Json<LoginRequest>,
+    jwt_secret: web::Data<JwtSecret>,
+) -> Result<HttpResponse, Error> {
+    let request = body.into_inner();
+
+    let user = match database::get_user_by_username(&pool, &request.username).await? {
+        Some(user) => user,
+        None => {
+            log::warn!("Login attempt for unknown username");
+            return Err(Error::Unauthorized);
+        }
+    };
+
+    let parsed_hash = match PasswordHash::new(&user.password_hash) {
+        Ok(hash) => hash,
+        Err(e) => {
+            log::error!("Stored password hash for user '{}' is invalid: {}", user.username, e);
+            return Err(Error::Unauthorized);
+        }
+    };
+
+    if Argon2::default()
+        .verify_password(request.password.as_bytes(), &parsed_hash)
+        .is_err()
+    {
+        log::warn!("Invalid password for username '{}'", user.username);
+        return Err(Error::Unauthorized);
+    }
+
+    log::info!("Login succeeded for username '{}'", user.username);
+    issue_session(&user, &jwt_secret)
+}
+
+/// POST /auth/refresh - Renew a session before it expires, without the
+/// caller resending credentials
+///
+/// The incoming token's signature and claims are still checked (`exp` isn't
+/// enforced by the decoder itself, so a token right on the edge of expiring
+/// can still refresh), but [`REFRESH_GRACE_PERIOD_SECS`] bounds how far past
+/// `exp` a token may be and still be accepted - a tampered, unsigned, or
+/// long-expired token can't be used to mint a fresh session.
+async fn refresh(
+    pool: web::Data<PgPool>,
+    body: web::Json<RefreshRequest>,
+    jwt_secret: web::Data<JwtSecret>,
+) -> Result<HttpResponse, Error> {
+    let request = body.into_inner();
+
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_exp = false;
+
+    let claims = match decode::<UserClaims>(
+        &request.access_token,
+        &DecodingKey::from_secret(jwt_secret.0.as_bytes()),
+        &validation,
+    ) {
+        Ok(data) => data.claims,
+        Err(e) => {
+            log::warn!("Refresh rejected: {}", e);
+            return Err(Error::Unauthorized);
+        }
+    };
+
+    let staleness_secs = chrono::Utc::now().timestamp() - claims.exp as i64;
+    if staleness_secs > REFRESH_GRACE_PERIOD_SECS {
+        log::warn!(
+            "Refresh rejected: access token for '{}' expired {}s ago, past the {}s grace window",
+            claims.sub, staleness_secs, REFRESH_GRACE_PERIOD_SECS
+        );
+        return Err(Error::Unauthorized);
+    }
+
+    // Re-read the user rather than trusting the roles already in the token,
+    // so a refreshed session picks up any role change made since login.
+    let user = match database::get_user_by_username(&pool, &claims.sub).await? {
+        Some(user) => user,
+        None => {
+            log::warn!("Refresh rejected: username '{}' no longer exists", claims.sub);
+            return Err(Error::Unauthorized);
+        }
+    };
+
+    log::info!("Refreshed session for username '{}'", user.username);
+    issue_session(&user, &jwt_secret)
+}
+
+/// Mints a fresh, short-lived [`UserClaims`] session token for `user`
+fn issue_session(user: &User, jwt_secret: &JwtSecret) -> Result<HttpResponse, Error> {
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = UserClaims {
+        sub: user.username.clone(),
+        roles: user.roles.clone(),
+        iat: now,
+        exp: now + SESSION_TTL_SECS as usize,
+    };
+
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(jwt_secret.0.as_bytes()))
+        .map_err(|e| {
+            log::error!("Failed to encode session JWT for user '{}': {}", user.username, e);
+            Error::ServiceUnavailable("session issuance failed".to_string())
+        })?;
+
+    Ok(HttpResponse::Ok().json(SessionResponse {
+        access_token: token,
+        token_type: "Bearer",
+        expires_in: SESSION_TTL_SECS,
+    }))
+}
+
+/// Configure the `/auth` routes
+pub fn configure_auth(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/auth")
+            .route("/token", web::post().to(issue_token))
+            .route("/login", web::post().to(login))
+            .route("/refresh", web::post().to(refresh))
+    );
+}
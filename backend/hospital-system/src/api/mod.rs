@@ -1,6 +1,14 @@
 pub mod patients;
 pub mod shared;
+pub mod shared_access;
+pub mod auth;
+pub mod sync;
+pub mod attachments;
 
 // Re-export configuration functions
 pub use patients::configure_patients;
-pub use shared::configure_shared;
\ No newline at end of file
+pub use shared::configure_shared;
+pub use shared_access::configure_shared_access;
+pub use auth::configure_auth;
+pub use sync::configure_sync;
+pub use attachments::configure_attachments;
\ No newline at end of file
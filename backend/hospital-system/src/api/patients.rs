@@ -1,11 +1,9 @@
 use actix_web::{web, HttpResponse};
 use sqlx::PgPool;
 use crate::database;
-use crate::models::{CreatePatient, UpdatePatient};
-use crate::utils::error_handler::{
-    handle_database_error,
-    handle_not_found,
-};
+use crate::models::{Patient, CreatePatient, UpdatePatient};
+use crate::middleware::{AuthenticatedUser, GuardedData, ReadPatients, ViewFlagged, WritePatients};
+use crate::utils::Error;
 
 /// Sanitize personal ID for logging
 fn sanitize_pid_for_log(pid: &str) -> String {
@@ -17,114 +15,200 @@ fn sanitize_pid_for_log(pid: &str) -> String {
 }
 
 /// GET /patients - Retrieve all patients
-async fn get_all_patients(pool: web::Data<PgPool>) -> HttpResponse {
-    match database::get_all_patients(&pool).await {
-        Ok(patients) => {
-            log::info!("Retrieved {} patients", patients.len());
-            HttpResponse::Ok().json(patients)
-        }
-        Err(e) => handle_database_error(e, "get_all_patients"),
-    }
+#[utoipa::path(
+    get,
+    path = "/patients",
+    tag = "patients",
+    responses(
+        (status = 200, description = "List of patients", body = [Patient]),
+        (status = 403, description = "Missing the read_patients scope"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_all_patients(
+    pool: web::Data<PgPool>,
+    _scope: GuardedData<ReadPatients>,
+) -> Result<HttpResponse, Error> {
+    let patients = database::get_all_patients(&pool).await?;
+    log::info!("Retrieved {} patients", patients.len());
+    Ok(HttpResponse::Ok().json(patients))
 }
 
 /// GET /patients/{id} - Retrieve a patient by ID
-async fn get_patient_by_id(
+#[utoipa::path(
+    get,
+    path = "/patients/{id}",
+    tag = "patients",
+    params(("id" = i32, Path, description = "Patient database ID")),
+    responses(
+        (status = 200, description = "Patient found", body = Patient),
+        (status = 404, description = "No patient with that ID"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_patient_by_id(
     pool: web::Data<PgPool>,
     id: web::Path<i32>,
-) -> HttpResponse {
+    _scope: GuardedData<ReadPatients>,
+) -> Result<HttpResponse, Error> {
     let patient_id = id.into_inner();
-    
-    match database::get_patient_by_id(&pool, patient_id).await {
-        Ok(Some(patient)) => {
-            log::info!("Retrieved patient with ID {}", patient_id);
-            HttpResponse::Ok().json(patient)
-        }
-        Ok(None) => handle_not_found("patient", &patient_id.to_string()),
-        Err(e) => handle_database_error(e, "get_patient_by_id"),
-    }
+
+    let patient = database::get_patient_by_id(&pool, patient_id)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("patient {}", patient_id)))?;
+
+    log::info!("Retrieved patient with ID {}", patient_id);
+    Ok(HttpResponse::Ok().json(patient))
 }
 
 /// GET /patients/personal/{personal_id} - Retrieve a patient by Swedish personal ID
-async fn get_patient_by_personal_id(
+#[utoipa::path(
+    get,
+    path = "/patients/personal/{personal_id}",
+    tag = "patients",
+    params(("personal_id" = String, Path, description = "Swedish personal ID (YYYYMMDD-XXXX)")),
+    responses(
+        (status = 200, description = "Patient found", body = Patient),
+        (status = 404, description = "No patient with that personal ID"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_patient_by_personal_id(
     pool: web::Data<PgPool>,
     personal_id: web::Path<String>,
-) -> HttpResponse {
+    _scope: GuardedData<ReadPatients>,
+) -> Result<HttpResponse, Error> {
     let pid = personal_id.into_inner();
     let sanitized = sanitize_pid_for_log(&pid);
-    
-    match database::get_patient_by_personal_id(&pool, &pid).await {
-        Ok(Some(patient)) => {
-            log::info!("Retrieved patient with personal_id {}", sanitized);
-            HttpResponse::Ok().json(patient)
-        }
-        Ok(None) => handle_not_found("patient", &sanitized),
-        Err(e) => handle_database_error(e, "get_patient_by_personal_id"),
-    }
+
+    let patient = database::get_patient_by_personal_id(&pool, &pid)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("patient {}", sanitized)))?;
+
+    log::info!("Retrieved patient with personal_id {}", sanitized);
+    Ok(HttpResponse::Ok().json(patient))
 }
 
 /// POST /patients - Create a new patient
-async fn create_patient(
+///
+/// Requires both the `write_patients` API key scope (the caller is an
+/// authorized integration) and a logged-in user session with the `staff`
+/// role (a specific person is accountable for the record being created).
+#[utoipa::path(
+    post,
+    path = "/patients",
+    tag = "patients",
+    request_body = CreatePatient,
+    responses(
+        (status = 201, description = "Patient created", body = Patient),
+        (status = 400, description = "Invalid patient payload"),
+        (status = 401, description = "No staff-role user session"),
+        (status = 403, description = "Missing the write_patients scope"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn create_patient(
     pool: web::Data<PgPool>,
     patient: web::Json<CreatePatient>,
-) -> HttpResponse {
+    _scope: GuardedData<WritePatients>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, Error> {
+    if !user.0.has_role("staff") {
+        log::warn!("User '{}' lacks the 'staff' role required to create patients", user.0.sub);
+        return Err(Error::Unauthorized);
+    }
+
     let patient_data = patient.into_inner();
     let sanitized = sanitize_pid_for_log(&patient_data.personal_id);
-    
-    match database::create_patient(&pool, patient_data).await {
-        Ok(created_patient) => {
-            log::info!("Created patient {} with ID {}", sanitized, created_patient.id);
-            HttpResponse::Created().json(created_patient)
-        }
-        Err(e) => handle_database_error(e, "create_patient"),
-    }
+
+    let created_patient = database::create_patient(&pool, patient_data).await?;
+    log::info!("Created patient {} with ID {}", sanitized, created_patient.id);
+    Ok(HttpResponse::Created().json(created_patient))
 }
 
 /// PUT /patients/{id} - Update an existing patient
-async fn update_patient(
+#[utoipa::path(
+    put,
+    path = "/patients/{id}",
+    tag = "patients",
+    params(("id" = i32, Path, description = "Patient database ID")),
+    request_body = UpdatePatient,
+    responses(
+        (status = 200, description = "Patient updated", body = Patient),
+        (status = 400, description = "Invalid patient payload"),
+        (status = 403, description = "Missing the write_patients scope"),
+        (status = 404, description = "No patient with that ID"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn update_patient(
     pool: web::Data<PgPool>,
     id: web::Path<i32>,
     patient: web::Json<UpdatePatient>,
-) -> HttpResponse {
+    _scope: GuardedData<WritePatients>,
+) -> Result<HttpResponse, Error> {
     let patient_id = id.into_inner();
     let patient_data = patient.into_inner();
-    let sanitized = sanitize_pid_for_log(&patient_data.personal_id);
-    
-    match database::update_patient(&pool, patient_id, patient_data).await {
-        Ok(Some(updated_patient)) => {
-            log::info!("Updated patient {} with ID {}", sanitized, patient_id);
-            HttpResponse::Ok().json(updated_patient)
-        }
-        Ok(None) => handle_not_found("patient", &patient_id.to_string()),
-        Err(e) => handle_database_error(e, "update_patient"),
-    }
+    let sanitized = patient_data
+        .personal_id
+        .as_deref()
+        .map(sanitize_pid_for_log)
+        .unwrap_or_else(|| "unchanged".to_string());
+
+    let updated_patient = database::update_patient(&pool, patient_id, patient_data)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("patient {}", patient_id)))?;
+
+    log::info!("Updated patient {} with ID {}", sanitized, patient_id);
+    Ok(HttpResponse::Ok().json(updated_patient))
 }
 
 /// DELETE /patients/{id} - Delete a patient
-async fn delete_patient(
+#[utoipa::path(
+    delete,
+    path = "/patients/{id}",
+    tag = "patients",
+    params(("id" = i32, Path, description = "Patient database ID")),
+    responses(
+        (status = 204, description = "Patient deleted"),
+        (status = 403, description = "Missing the write_patients scope"),
+        (status = 404, description = "No patient with that ID"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn delete_patient(
     pool: web::Data<PgPool>,
     id: web::Path<i32>,
-) -> HttpResponse {
+    _scope: GuardedData<WritePatients>,
+) -> Result<HttpResponse, Error> {
     let patient_id = id.into_inner();
-    
-    match database::delete_patient(&pool, patient_id).await {
-        Ok(true) => {
-            log::info!("Deleted patient with ID {}", patient_id);
-            HttpResponse::NoContent().finish()
-        }
-        Ok(false) => handle_not_found("patient", &patient_id.to_string()),
-        Err(e) => handle_database_error(e, "delete_patient"),
+
+    if !database::delete_patient(&pool, patient_id).await? {
+        return Err(Error::NotFound(format!("patient {}", patient_id)));
     }
+
+    log::info!("Deleted patient with ID {}", patient_id);
+    Ok(HttpResponse::NoContent().finish())
 }
 
 /// GET /patients/flagged - Retrieve all patients flagged by police system
-async fn get_flagged_patients(pool: web::Data<PgPool>) -> HttpResponse {
-    match database::get_flagged_patients(&pool).await {
-        Ok(flagged_patients) => {
-            log::info!("Retrieved {} flagged patients", flagged_patients.len());
-            HttpResponse::Ok().json(flagged_patients)
-        }
-        Err(e) => handle_database_error(e, "get_flagged_patients"),
-    }
+#[utoipa::path(
+    get,
+    path = "/patients/flagged",
+    tag = "patients",
+    responses(
+        (status = 200, description = "List of flagged patients", body = [Patient]),
+        (status = 403, description = "Missing the view_flagged scope"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_flagged_patients(
+    pool: web::Data<PgPool>,
+    _scope: GuardedData<ViewFlagged>,
+) -> Result<HttpResponse, Error> {
+    let flagged_patients = database::get_flagged_patients(&pool).await?;
+    log::info!("Retrieved {} flagged patients", flagged_patients.len());
+    Ok(HttpResponse::Ok().json(flagged_patients))
 }
 
 /// Configure all patient-related routes
@@ -1,11 +1,77 @@
-use actix_web::{web, HttpResponse};
+use std::net::IpAddr;
+
+use actix_web::{web, HttpMessage, HttpResponse};
+use serde::Serialize;
 use sqlx::PgPool;
+use utoipa::ToSchema;
 use crate::database;
-use crate::utils::error_handler::{
-    handle_database_error,
-    handle_not_found,
-};
+use crate::models::{CreateAccessRequest, Patient, SharedAccessRequest};
+use crate::utils::{Error, PageParams};
 use crate::utils::audit::{AuditLog, EventType, Action, AuditResult, extract_actor_from_request};
+use crate::tls::ClientCertInfo;
+
+/// A keyset-paginated page of patients, modeled on object-store list APIs:
+/// `next_cursor` is the `id` of the last row in this page, or `null` once
+/// the caller has reached the end of the table.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PatientPage {
+    pub items: Vec<Patient>,
+    pub next_cursor: Option<i32>,
+}
+
+/// Describes the verified mTLS client certificate attached to this request,
+/// for audit logging only - enforcement itself happens at the TLS layer,
+/// on the dedicated /api/shared listener `main.rs` binds whenever
+/// `TLS_CLIENT_CA_PATH` is set (see `split_shared_api_listener`). A handler
+/// running here can assume a certificate was presented and verified
+/// whenever that listener is in use; this never falls back to "none" for a
+/// real request on that listener, only for local/dev setups without mTLS.
+fn describe_client_cert(req: &actix_web::HttpRequest) -> String {
+    match req.conn_data::<ClientCertInfo>() {
+        Some(info) => format!("subject='{}' fingerprint={}", info.subject, info.fingerprint),
+        None => "none".to_string(),
+    }
+}
+
+/// Consults the `shared_access` break-glass table before a read is allowed
+/// to proceed. `personal_id = None` checks for a blanket "all" grant, as the
+/// page/flagged endpoints need; `Some(pid)` also accepts a grant naming
+/// that specific patient.
+///
+/// On denial, logs a `BreakGlassRead`/`Failure` audit entry itself - the
+/// caller doesn't get a chance to log one, since the request is rejected
+/// with `403` before any patient data is touched.
+async fn require_access(
+    pool: &PgPool,
+    personal_id: Option<&str>,
+    actor: &str,
+    ip: Option<IpAddr>,
+) -> Result<(), Error> {
+    let resource = match personal_id {
+        Some(pid) => format!("patient:{}", pid),
+        None => "patients:all".to_string(),
+    };
+
+    if database::check_access(pool, personal_id).await? {
+        return Ok(());
+    }
+
+    AuditLog::new(
+        EventType::BreakGlassRead,
+        actor.to_string(),
+        Action::Read,
+        resource.clone(),
+        AuditResult::Failure,
+    )
+    .with_ip(ip)
+    .with_details("No approved shared_access grant covers this resource".to_string())
+    .write();
+
+    Err(Error::Forbidden(format!(
+        "no approved access grant for {} - request access via POST /api/shared/access-requests",
+        resource
+    )))
+}
 
 /// GET /api/shared/patients/{personal_id} - Retrieve patient info by Swedish personal ID
 /// 
@@ -13,28 +79,47 @@ use crate::utils::audit::{AuditLog, EventType, Action, AuditResult, extract_acto
 /// by querying their personal_id (Swedish format: YYYYMMDD-XXXX)
 /// 
 /// **REQUIRES AUTHENTICATION**: X-API-Key header must be present
-async fn get_shared_patient_info(
+#[utoipa::path(
+    get,
+    path = "/api/shared/patients/{personal_id}",
+    tag = "shared",
+    params(("personal_id" = String, Path, description = "Swedish personal ID (YYYYMMDD-XXXX)")),
+    responses(
+        (status = 200, description = "Patient found", body = Patient),
+        (status = 404, description = "No patient with that personal ID"),
+        (status = 401, description = "Missing or invalid credentials"),
+        (status = 403, description = "No approved break-glass access grant covers this patient"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_shared_patient_info(
     pool: web::Data<PgPool>,
     personal_id: web::Path<String>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+) -> Result<HttpResponse, Error> {
     let pid = personal_id.into_inner();
     let actor = extract_actor_from_request(&req);
     let ip = req.peer_addr().map(|a| a.ip());
-    
+
     // Sanitize log output - redact personal ID
     let sanitized_pid = if pid.len() >= 9 {
         format!("{}-****", &pid[..8])
     } else {
         "INVALID-****".to_string()
     };
-    
-    log::info!("Shared API: Authenticated query for patient {}", sanitized_pid);
-    
+
+    require_access(&pool, Some(&pid), &actor, ip).await?;
+
+    log::info!(
+        "Shared API: Authenticated query for patient {} (client cert: {})",
+        sanitized_pid,
+        describe_client_cert(&req)
+    );
+
     match database::get_patient_by_personal_id(&pool, &pid).await {
         Ok(Some(patient)) => {
             AuditLog::new(
-                EventType::SharedApiAccess,
+                EventType::BreakGlassRead,
                 actor,
                 Action::Read,
                 format!("patient:{}", sanitized_pid),
@@ -42,13 +127,13 @@ async fn get_shared_patient_info(
             )
             .with_ip(ip)
             .write();
-            
+
             log::info!("Shared API: Patient record found for {}", sanitized_pid);
-            HttpResponse::Ok().json(patient)
+            Ok(HttpResponse::Ok().json(patient))
         }
         Ok(None) => {
             AuditLog::new(
-                EventType::SharedApiAccess,
+                EventType::BreakGlassRead,
                 actor,
                 Action::Read,
                 format!("patient:{}", sanitized_pid),
@@ -57,13 +142,13 @@ async fn get_shared_patient_info(
             .with_ip(ip)
             .with_details("Patient not found".to_string())
             .write();
-            
+
             log::info!("Shared API: No patient record for {}", sanitized_pid);
-            handle_not_found("patient", &sanitized_pid)
+            Err(Error::NotFound(format!("patient {}", sanitized_pid)))
         }
         Err(e) => {
             AuditLog::new(
-                EventType::SharedApiAccess,
+                EventType::BreakGlassRead,
                 actor,
                 Action::Read,
                 format!("patient:{}", sanitized_pid),
@@ -72,55 +157,84 @@ async fn get_shared_patient_info(
             .with_ip(ip)
             .with_details(format!("Database error: {}", e))
             .write();
-            
-            handle_database_error(e, "get_shared_patient_info")
+
+            Err(e.into())
         }
     }
 }
 
-/// GET /api/shared/patients - Retrieve all patients
-/// 
-/// This endpoint allows the police system to retrieve a complete list of all patients
-/// for cross-referencing with their suspect database
-/// 
+/// GET /api/shared/patients - Retrieve a page of patients
+///
+/// This endpoint allows the police system to page through patients for
+/// cross-referencing with their suspect database, instead of the whole
+/// table being serialized in one response.
+///
 /// **REQUIRES AUTHENTICATION**: X-API-Key header must be present
-async fn get_all_shared_patients(
+#[utoipa::path(
+    get,
+    path = "/api/shared/patients",
+    tag = "shared",
+    params(
+        ("limit" = Option<i64>, Query, description = "Max rows to return (default 50, max 500)"),
+        ("after" = Option<i32>, Query, description = "Resume after this patient id"),
+    ),
+    responses(
+        (status = 200, description = "A page of patients", body = PatientPage),
+        (status = 401, description = "Missing or invalid credentials"),
+        (status = 403, description = "No approved break-glass \"all\" access grant"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_all_shared_patients(
     pool: web::Data<PgPool>,
+    page: web::Query<PageParams>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+) -> Result<HttpResponse, Error> {
     let actor = extract_actor_from_request(&req);
     let ip = req.peer_addr().map(|a| a.ip());
-    
-    log::info!("Shared API: Authenticated request for all patients");
-    
-    match database::get_all_patients(&pool).await {
+    let limit = page.limit();
+    let after_id = page.after_id();
+
+    require_access(&pool, None, &actor, ip).await?;
+
+    log::info!(
+        "Shared API: Authenticated request for patients page (limit={}, after={}) (client cert: {})",
+        limit, after_id, describe_client_cert(&req)
+    );
+
+    match database::get_patients_page(&pool, limit, after_id).await {
         Ok(patients) => {
             AuditLog::new(
-                EventType::SharedApiAccess,
+                EventType::BreakGlassRead,
                 actor,
                 Action::Read,
-                format!("patients:all (count: {})", patients.len()),
+                format!("patients:page (size: {})", patients.len()),
                 AuditResult::Success,
             )
             .with_ip(ip)
             .write();
-            
+
             log::info!("Shared API: Returning {} patient records", patients.len());
-            HttpResponse::Ok().json(patients)
+            let next_cursor = if patients.len() as i64 == limit {
+                patients.last().map(|p| p.id)
+            } else {
+                None
+            };
+            Ok(HttpResponse::Ok().json(PatientPage { items: patients, next_cursor }))
         }
         Err(e) => {
             AuditLog::new(
-                EventType::SharedApiAccess,
+                EventType::BreakGlassRead,
                 actor,
                 Action::Read,
-                "patients:all".to_string(),
+                "patients:page".to_string(),
                 AuditResult::Failure,
             )
             .with_ip(ip)
             .with_details(format!("Database error: {}", e))
             .write();
-            
-            handle_database_error(e, "get_all_shared_patients")
+
+            Err(e.into())
         }
     }
 }
@@ -132,14 +246,30 @@ async fn get_all_shared_patients(
 /// via postgres_fdw triggers.
 /// 
 /// **REQUIRES AUTHENTICATION**: X-API-Key header must be present
-async fn get_shared_flagged_patients(
+#[utoipa::path(
+    get,
+    path = "/api/shared/patients/flagged",
+    tag = "shared",
+    responses(
+        (status = 200, description = "List of flagged patients", body = [Patient]),
+        (status = 401, description = "Missing or invalid credentials"),
+        (status = 403, description = "No approved break-glass \"all\" access grant"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_shared_flagged_patients(
     pool: web::Data<PgPool>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+) -> Result<HttpResponse, Error> {
     let actor = extract_actor_from_request(&req);
     let ip = req.peer_addr().map(|a| a.ip());
-    
-    log::info!("Shared API: Authenticated request for flagged patients");
+
+    require_access(&pool, None, &actor, ip).await?;
+
+    log::info!(
+        "Shared API: Authenticated request for flagged patients (client cert: {})",
+        describe_client_cert(&req)
+    );
     
     match database::get_flagged_patients(&pool).await {
         Ok(flagged_patients) => {
@@ -154,7 +284,7 @@ async fn get_shared_flagged_patients(
             .write();
             
             log::info!("Shared API: Returning {} flagged records", flagged_patients.len());
-            HttpResponse::Ok().json(flagged_patients)
+            Ok(HttpResponse::Ok().json(flagged_patients))
         }
         Err(e) => {
             AuditLog::new(
@@ -167,28 +297,95 @@ async fn get_shared_flagged_patients(
             .with_ip(ip)
             .with_details(format!("Database error: {}", e))
             .write();
-            
-            handle_database_error(e, "get_shared_flagged_patients")
+
+            Err(e.into())
+        }
+    }
+}
+
+/// POST /api/shared/access-requests - Request break-glass access to a
+/// specific patient's records, or to every patient ("all").
+///
+/// The request starts in the `Requested` state and auto-approves after
+/// `wait_time_days` unless a hospital actor denies it first via
+/// `POST /shared-access/{id}/deny` - see [`crate::shared_access`].
+///
+/// **REQUIRES AUTHENTICATION**: a bearer JWT, or an X-API-Key from
+/// `SHARED_API_KEYS` while `LEGACY_API_KEY_ENABLED` is on
+#[utoipa::path(
+    post,
+    path = "/api/shared/access-requests",
+    tag = "shared",
+    request_body = CreateAccessRequest,
+    responses(
+        (status = 201, description = "Access request created", body = SharedAccessRequest),
+        (status = 401, description = "Missing or invalid credentials"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn create_access_request(
+    pool: web::Data<PgPool>,
+    request: web::Json<CreateAccessRequest>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let actor = extract_actor_from_request(&req);
+    let ip = req.peer_addr().map(|a| a.ip());
+    let request = request.into_inner();
+
+    if let Some(days) = request.wait_time_days {
+        if days < crate::models::shared_access::MIN_WAIT_TIME_DAYS {
+            return Err(Error::Validation(format!(
+                "wait_time_days must be at least {}",
+                crate::models::shared_access::MIN_WAIT_TIME_DAYS
+            )));
         }
     }
+
+    let resource = match &request.personal_id {
+        Some(pid) => format!("patient:{}", pid),
+        None => "patients:all".to_string(),
+    };
+
+    let created = database::create_access_request(&pool, &actor, request).await?;
+
+    AuditLog::new(
+        EventType::AccessRequested,
+        actor.clone(),
+        Action::Create,
+        resource,
+        AuditResult::Success,
+    )
+    .with_ip(ip)
+    .write();
+
+    log::info!(
+        "Shared API: '{}' requested {} access to {} (request id {})",
+        actor,
+        created.access_type,
+        created.personal_id.as_deref().unwrap_or("all"),
+        created.id
+    );
+    Ok(HttpResponse::Created().json(created))
 }
 
 /// Configure shared/inter-system API routes
-/// 
+///
 /// These endpoints are designed to be called by the police system
 /// to check if patients have medical records or to view flagged patients.
-/// 
+///
 /// **ALL ROUTES REQUIRE API KEY AUTHENTICATION**
-/// 
+///
 /// Routes:
 /// - GET /patients - List all patients
 /// - GET /patients/flagged - List flagged patients (auto-synced from police)
 /// - GET /patients/{personal_id} - Check specific person
-/// 
+/// - POST /access-requests - Request break-glass access to a patient, or "all"
+///
 /// Note: This function is now called within a scope that has ApiKeyAuth middleware applied
 pub fn configure_shared(cfg: &mut web::ServiceConfig) {
     cfg
         .route("/patients", web::get().to(get_all_shared_patients))
         .route("/patients/flagged", web::get().to(get_shared_flagged_patients))
-        .route("/patients/{personal_id}", web::get().to(get_shared_patient_info));
+        .route("/patients/{personal_id}", web::get().to(get_shared_patient_info))
+        .route("/access-requests", web::post().to(create_access_request));
 }
\ No newline at end of file
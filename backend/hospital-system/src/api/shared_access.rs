@@ -0,0 +1,118 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+
+use crate::database;
+use crate::middleware::{AuthenticatedUser, GuardedData, WritePatients};
+use crate::utils::audit::{Action, AuditLog, AuditResult, EventType};
+use crate::utils::Error;
+
+/// POST /shared-access/{id}/approve - Explicitly approve a break-glass
+/// access request before its wait period would auto-approve it.
+///
+/// Requires both the `write_patients` API key scope and a logged-in user
+/// session with the `staff` role, matching [`crate::api::patients::create_patient`] -
+/// a specific person is accountable for granting access to medical records.
+#[utoipa::path(
+    post,
+    path = "/shared-access/{id}/approve",
+    tag = "shared",
+    params(("id" = i32, Path, description = "Access request ID")),
+    responses(
+        (status = 200, description = "Access request approved", body = crate::models::SharedAccessRequest),
+        (status = 401, description = "No staff-role user session"),
+        (status = 403, description = "Missing the write_patients scope"),
+        (status = 404, description = "No request with that ID, or it is no longer Requested"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn approve_access_request(
+    pool: web::Data<PgPool>,
+    id: web::Path<i32>,
+    _scope: GuardedData<WritePatients>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, Error> {
+    if !user.0.has_role("staff") {
+        log::warn!("User '{}' lacks the 'staff' role required to approve access requests", user.0.sub);
+        return Err(Error::Unauthorized);
+    }
+
+    let request_id = id.into_inner();
+
+    let approved = database::approve_access_request(&pool, request_id, &user.0.sub)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("pending access request {}", request_id)))?;
+
+    AuditLog::new(
+        EventType::AccessApproved,
+        user.0.sub.clone(),
+        Action::Update,
+        format!("shared_access:{}", request_id),
+        AuditResult::Success,
+    )
+    .write();
+
+    log::info!("Access request {} approved by '{}'", request_id, user.0.sub);
+    Ok(HttpResponse::Ok().json(approved))
+}
+
+/// POST /shared-access/{id}/deny - Deny a break-glass access request,
+/// stopping it before it can auto-approve (or revoking it if already
+/// approved).
+///
+/// Requires both the `write_patients` API key scope and a logged-in user
+/// session with the `staff` role.
+#[utoipa::path(
+    post,
+    path = "/shared-access/{id}/deny",
+    tag = "shared",
+    params(("id" = i32, Path, description = "Access request ID")),
+    responses(
+        (status = 200, description = "Access request denied", body = crate::models::SharedAccessRequest),
+        (status = 401, description = "No staff-role user session"),
+        (status = 403, description = "Missing the write_patients scope"),
+        (status = 404, description = "No request with that ID, or it is already Denied/Expired"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn deny_access_request(
+    pool: web::Data<PgPool>,
+    id: web::Path<i32>,
+    _scope: GuardedData<WritePatients>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, Error> {
+    if !user.0.has_role("staff") {
+        log::warn!("User '{}' lacks the 'staff' role required to deny access requests", user.0.sub);
+        return Err(Error::Unauthorized);
+    }
+
+    let request_id = id.into_inner();
+
+    let denied = database::deny_access_request(&pool, request_id, &user.0.sub)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("open access request {}", request_id)))?;
+
+    AuditLog::new(
+        EventType::AccessDenied,
+        user.0.sub.clone(),
+        Action::Update,
+        format!("shared_access:{}", request_id),
+        AuditResult::Success,
+    )
+    .write();
+
+    log::info!("Access request {} denied by '{}'", request_id, user.0.sub);
+    Ok(HttpResponse::Ok().json(denied))
+}
+
+/// Configure break-glass access approval/denial routes.
+///
+/// Routes:
+/// - POST /shared-access/{id}/approve
+/// - POST /shared-access/{id}/deny
+pub fn configure_shared_access(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/shared-access")
+            .route("/{id}/approve", web::post().to(approve_access_request))
+            .route("/{id}/deny", web::post().to(deny_access_request)),
+    );
+}
@@ -0,0 +1,84 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::database;
+use crate::sync::signature;
+use crate::config::SyncSharedSecret;
+
+/// Inbound flag-sync payload from the police system's outbox worker
+#[derive(Deserialize, Serialize)]
+struct FlagSyncEvent {
+    personal_id: String,
+    flag: bool,
+    sequence: i64,
+}
+
+/// POST /api/shared/sync/flag - Applies a suspect-flag change pushed by the
+/// police system's sync worker.
+///
+/// This does not use the JWT/API-key auth applied to the rest of
+/// `/api/shared/*` - instead, the request must carry a valid `X-Signature`
+/// HMAC (computed over the canonical JSON body, sequence number and
+/// timestamp) and an `X-Timestamp` within a ±5 minute window, to make the
+/// channel tamper-evident and replay-resistant. Delivery is idempotent: a
+/// retried `(source, sequence)` pair is a no-op.
+async fn sync_flag(
+    pool: web::Data<PgPool>,
+    shared_secret: web::Data<SyncSharedSecret>,
+    body: web::Bytes,
+    req: HttpRequest,
+) -> HttpResponse {
+    let signature_hex = match req.headers().get("X-Signature").and_then(|h| h.to_str().ok()) {
+        Some(v) => v.to_string(),
+        None => return HttpResponse::Unauthorized().json(serde_json::json!({ "error": "X-Signature header required" })),
+    };
+
+    let timestamp: DateTime<Utc> = match req.headers().get("X-Timestamp").and_then(|h| h.to_str().ok()).and_then(|v| v.parse::<i64>().ok()) {
+        Some(secs) => match Utc.timestamp_opt(secs, 0).single() {
+            Some(ts) => ts,
+            None => return HttpResponse::BadRequest().json(serde_json::json!({ "error": "Invalid X-Timestamp header" })),
+        },
+        None => return HttpResponse::Unauthorized().json(serde_json::json!({ "error": "X-Timestamp header required" })),
+    };
+
+    let canonical_json = match std::str::from_utf8(&body) {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::BadRequest().json(serde_json::json!({ "error": "Body must be valid UTF-8 JSON" })),
+    };
+
+    let event: FlagSyncEvent = match serde_json::from_str(canonical_json) {
+        Ok(event) => event,
+        Err(_) => return HttpResponse::BadRequest().json(serde_json::json!({ "error": "Invalid sync event payload" })),
+    };
+
+    if !signature::verify(&shared_secret.0, canonical_json, event.sequence, timestamp, &signature_hex) {
+        log::warn!("Sync: rejected flag sync for sequence {} - signature verification failed", event.sequence);
+        return HttpResponse::Unauthorized().json(serde_json::json!({ "error": "Signature verification failed" }));
+    }
+
+    match database::apply_flag_sync(&pool, "police-system", event.sequence, &event.personal_id, event.flag).await {
+        Ok(true) => {
+            log::info!("Sync: applied flag={} for sequence {}", event.flag, event.sequence);
+            HttpResponse::Ok().json(serde_json::json!({ "status": "applied" }))
+        }
+        Ok(false) => {
+            log::info!("Sync: sequence {} already applied, skipping duplicate delivery", event.sequence);
+            HttpResponse::Ok().json(serde_json::json!({ "status": "duplicate" }))
+        }
+        Err(e) => {
+            log::error!("Sync: failed to apply flag sync for sequence {}: {}", event.sequence, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": "Failed to apply sync event" }))
+        }
+    }
+}
+
+/// Configure the inbound sync routes. Mounted outside the JWT-protected
+/// `/api/shared` scope since this endpoint authenticates via HMAC signature
+/// instead of a bearer token.
+pub fn configure_sync(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/shared/sync")
+            .route("/flag", web::post().to(sync_flag))
+    );
+}
@@ -0,0 +1,13 @@
+pub mod storage;
+
+pub use storage::{save_multipart_field, StoredFile};
+
+/// Where uploaded attachments are streamed to and the limits enforced while
+/// streaming - registered once as `web::Data<AttachmentConfig>` so handlers
+/// don't each read the environment themselves.
+#[derive(Clone)]
+pub struct AttachmentConfig {
+    pub storage_dir: String,
+    pub max_size_bytes: u64,
+    pub allowed_content_types: Vec<String>,
+}
@@ -0,0 +1,123 @@
+use actix_multipart::Field;
+use futures_util::TryStreamExt;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use crate::utils::Error;
+
+/// Metadata gathered while streaming one multipart field to disk.
+pub struct StoredFile {
+    pub storage_path: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+}
+
+/// Strips characters from a client-supplied filename that would let it
+/// break out of the quoted `filename="..."` parameter when later
+/// interpolated into a `Content-Disposition` response header - notably `"`,
+/// which could otherwise inject additional parameters (e.g. a `filename*=`
+/// override) into every future download of the attachment. Control
+/// characters are stripped too, for the same reason. The file itself is
+/// still written under a generated UUID name (see below), so this only
+/// protects the *displayed* filename, not storage.
+fn sanitize_filename(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| *c != '"' && !c.is_control())
+        .collect();
+
+    if cleaned.is_empty() {
+        "upload".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Streams a single multipart `field` to `storage_dir` chunk-by-chunk -
+/// the file is never buffered into memory in full, so an upload can't
+/// exhaust the server's RAM regardless of its declared size.
+///
+/// Rejects the upload (and removes the partial file) if the content type
+/// isn't in `allowed_content_types` or the stream exceeds `max_size_bytes`
+/// before it ends. The file is written under a generated UUID name, not the
+/// client-supplied filename, so nothing a client sends can escape
+/// `storage_dir` or collide with another upload.
+pub async fn save_multipart_field(
+    mut field: Field,
+    storage_dir: &str,
+    max_size_bytes: u64,
+    allowed_content_types: &[String],
+) -> Result<StoredFile, Error> {
+    let content_type = field
+        .content_type()
+        .map(|mime| mime.essence_str().to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    if !allowed_content_types.iter().any(|allowed| allowed == &content_type) {
+        return Err(Error::UnsupportedMediaType(format!(
+            "content type '{}' is not accepted for attachments",
+            content_type
+        )));
+    }
+
+    let filename = field
+        .content_disposition()
+        .get_filename()
+        .map(sanitize_filename)
+        .unwrap_or_else(|| "upload".to_string());
+
+    tokio::fs::create_dir_all(storage_dir).await.map_err(|e| {
+        log::error!("Failed to create attachment storage directory '{}': {}", storage_dir, e);
+        Error::ServiceUnavailable("attachment storage is unavailable".to_string())
+    })?;
+
+    let storage_name = Uuid::new_v4().to_string();
+    let storage_path = std::path::Path::new(storage_dir).join(&storage_name);
+
+    let mut file = tokio::fs::File::create(&storage_path).await.map_err(|e| {
+        log::error!("Failed to create attachment file '{}': {}", storage_path.display(), e);
+        Error::ServiceUnavailable("attachment storage is unavailable".to_string())
+    })?;
+
+    let mut hasher = Sha256::new();
+    let mut size_bytes: u64 = 0;
+
+    while let Some(chunk) = field.try_next().await.map_err(|e| {
+        Error::Validation(format!("failed to read upload stream: {}", e))
+    })? {
+        size_bytes += chunk.len() as u64;
+
+        if size_bytes > max_size_bytes {
+            drop(file);
+            let _ = tokio::fs::remove_file(&storage_path).await;
+            return Err(Error::PayloadTooLarge(format!(
+                "attachment exceeds the maximum allowed size of {} bytes",
+                max_size_bytes
+            )));
+        }
+
+        hasher.update(&chunk);
+        file.write_all(&chunk).await.map_err(|e| {
+            log::error!("Failed to write attachment chunk to '{}': {}", storage_path.display(), e);
+            Error::ServiceUnavailable("attachment storage is unavailable".to_string())
+        })?;
+    }
+
+    file.flush().await.map_err(|e| {
+        log::error!("Failed to flush attachment file '{}': {}", storage_path.display(), e);
+        Error::ServiceUnavailable("attachment storage is unavailable".to_string())
+    })?;
+
+    let sha256 = hex::encode(hasher.finalize());
+
+    Ok(StoredFile {
+        storage_path: storage_path.to_string_lossy().to_string(),
+        filename,
+        content_type,
+        size_bytes: size_bytes as i64,
+        sha256,
+    })
+}
@@ -1,18 +1,62 @@
 use std::env;
 
+/// Newtype wrappers around plain `String` config values that get registered
+/// as `web::Data` - actix resolves `web::Data<T>` extractors by type alone,
+/// so using bare `String` for more than one value would make them
+/// indistinguishable to handlers.
+#[derive(Clone)]
+pub struct JwtSecret(pub String);
+#[derive(Clone)]
+pub struct JwtAudience(pub String);
+#[derive(Clone)]
+pub struct SyncSharedSecret(pub String);
+
 #[derive(Clone)]
 pub struct Config {
     pub database_url: String,
     pub server_port: String,
-    pub api_key: String,
     pub allowed_origins: Vec<String>,
     pub rate_limit_per_minute: u64,
     pub enable_tls: bool,
     pub tls_cert_path: Option<String>,
     pub tls_key_path: Option<String>,
-    // New fields for shared API rate limiting
-    pub shared_api_rate_limit_per_second: u64,
-    pub shared_api_rate_limit_burst: u32,
+    // Optional mutual TLS for /api/shared: when set (and ACME is not in
+    // use), /api/shared is bound on its own listener (`shared_api_tls_port`)
+    // that requires a client certificate signed by this CA. /patients,
+    // /auth/*, /health and the ACME challenge route keep running on the
+    // regular `server_port` listener without a client cert.
+    pub tls_client_ca_path: Option<String>,
+    // Port for the dedicated /api/shared listener - only bound when
+    // `tls_client_ca_path` is set and ACME is not in use; kept separate
+    // from `server_port` so mutual TLS enforcement can't accidentally
+    // apply to any route other than /api/shared
+    pub shared_api_tls_port: String,
+    // ACME (Let's Encrypt) automatic certificate provisioning
+    pub enable_acme: bool,
+    pub acme_directory_url: String,
+    pub acme_domain: Option<String>,
+    pub acme_contact_email: Option<String>,
+    pub acme_state_dir: String,
+    // Per-client rate limiting and anomaly flagging for /api/shared, keyed
+    // by the authenticated client identity rather than by IP
+    pub shared_api_requests_per_minute: u32,
+    pub shared_api_bulk_requests_per_hour: u32,
+    pub shared_api_anomaly_distinct_ids_threshold: u32,
+    pub shared_api_anomaly_window_secs: u64,
+    pub shared_api_anomaly_not_found_threshold: u32,
+    // JWT-based auth for the shared API
+    pub jwt_secret: String,
+    pub jwt_audience: String,
+    pub legacy_api_key_enabled: bool,
+    // Shared secret for HMAC-signed inbound flag sync from the police system
+    pub sync_shared_secret: String,
+    // Patient document attachments
+    pub attachment_storage_dir: String,
+    pub attachment_max_size_bytes: u64,
+    pub attachment_allowed_content_types: Vec<String>,
+    // Minimum response body size, in bytes, before the compression
+    // middleware bothers gzip/deflate-encoding it
+    pub compression_min_size_bytes: usize,
 }
 
 impl Config {
@@ -23,14 +67,6 @@ impl Config {
         let server_port = env::var("SERVER_PORT")
             .unwrap_or_else(|_| "8001".to_string());
         
-        let api_key = env::var("API_KEY")
-            .map_err(|_| "API_KEY must be set for security".to_string())?;
-        
-        // Validate API key length
-        if api_key.len() < 32 {
-            return Err("API_KEY must be at least 32 characters long".to_string());
-        }
-        
         // Parse allowed origins from environment variable
         let allowed_origins_str = env::var("ALLOWED_ORIGINS")
             .unwrap_or_else(|_| {
@@ -60,33 +96,133 @@ impl Config {
         
         let tls_cert_path = env::var("TLS_CERT_PATH").ok();
         let tls_key_path = env::var("TLS_KEY_PATH").ok();
+        let tls_client_ca_path = env::var("TLS_CLIENT_CA_PATH").ok();
+        let shared_api_tls_port = env::var("SHARED_API_TLS_PORT")
+            .unwrap_or_else(|_| "8444".to_string());
         
-        if enable_tls && (tls_cert_path.is_none() || tls_key_path.is_none()) {
-            return Err("TLS_CERT_PATH and TLS_KEY_PATH must be set when ENABLE_TLS=true".to_string());
+        let enable_acme = env::var("ENABLE_ACME")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let acme_directory_url = env::var("ACME_DIRECTORY_URL")
+            .unwrap_or_else(|_| "https://acme-v02.api.letsencrypt.org/directory".to_string());
+
+        let acme_domain = env::var("ACME_DOMAIN").ok();
+        let acme_contact_email = env::var("ACME_CONTACT_EMAIL").ok();
+
+        let acme_state_dir = env::var("ACME_STATE_DIR")
+            .unwrap_or_else(|_| "./acme-state".to_string());
+
+        if enable_acme && (acme_domain.is_none() || acme_contact_email.is_none()) {
+            return Err("ACME_DOMAIN and ACME_CONTACT_EMAIL must be set when ENABLE_ACME=true".to_string());
+        }
+
+        if enable_tls && !enable_acme && (tls_cert_path.is_none() || tls_key_path.is_none()) {
+            return Err("TLS_CERT_PATH and TLS_KEY_PATH must be set when ENABLE_TLS=true and ENABLE_ACME=false".to_string());
         }
         
         // Parse shared API rate limiting configuration
-        let shared_api_rate_limit_per_second = env::var("SHARED_API_RATE_LIMIT_PER_SECOND")
-            .unwrap_or_else(|_| "1".to_string())
+        let shared_api_requests_per_minute = env::var("SHARED_API_REQUESTS_PER_MINUTE")
+            .unwrap_or_else(|_| "120".to_string())
             .parse()
-            .unwrap_or(1);
-        
-        let shared_api_rate_limit_burst = env::var("SHARED_API_RATE_LIMIT_BURST")
+            .unwrap_or(120);
+
+        let shared_api_bulk_requests_per_hour = env::var("SHARED_API_BULK_REQUESTS_PER_HOUR")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30);
+
+        let shared_api_anomaly_distinct_ids_threshold = env::var("SHARED_API_ANOMALY_DISTINCT_IDS_THRESHOLD")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse()
+            .unwrap_or(20);
+
+        let shared_api_anomaly_window_secs = env::var("SHARED_API_ANOMALY_WINDOW_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .unwrap_or(60);
+
+        let shared_api_anomaly_not_found_threshold = env::var("SHARED_API_ANOMALY_NOT_FOUND_THRESHOLD")
             .unwrap_or_else(|_| "5".to_string())
             .parse()
             .unwrap_or(5);
-        
+
+        // JWT secret used to sign/verify tokens minted by /auth/token
+        let jwt_secret = env::var("JWT_SECRET")
+            .map_err(|_| "JWT_SECRET must be set for shared API authentication".to_string())?;
+
+        if jwt_secret.len() < 32 {
+            return Err("JWT_SECRET must be at least 32 characters long".to_string());
+        }
+
+        let jwt_audience = env::var("JWT_AUDIENCE")
+            .unwrap_or_else(|_| "hospital-system".to_string());
+
+        // Allows partner keys from SHARED_API_KEYS to keep working on
+        // /api/shared/* while consumers migrate to bearer tokens. Disable
+        // once migration is done.
+        let legacy_api_key_enabled = env::var("LEGACY_API_KEY_ENABLED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let sync_shared_secret = env::var("SYNC_SHARED_SECRET")
+            .map_err(|_| "SYNC_SHARED_SECRET must be set for cross-system flag sync".to_string())?;
+
+        // Patient document attachments - where uploaded files are streamed
+        // to, how large one upload may be, and which content types are
+        // accepted for clinical scans/images.
+        let attachment_storage_dir = env::var("ATTACHMENT_STORAGE_DIR")
+            .unwrap_or_else(|_| "./attachments".to_string());
+
+        let attachment_max_size_bytes = env::var("ATTACHMENT_MAX_SIZE_BYTES")
+            .unwrap_or_else(|_| "26214400".to_string()) // 25 MiB
+            .parse()
+            .unwrap_or(26_214_400);
+
+        let attachment_allowed_content_types_str = env::var("ATTACHMENT_ALLOWED_CONTENT_TYPES")
+            .unwrap_or_else(|_| "application/pdf,image/png,image/jpeg,application/dicom".to_string());
+
+        let attachment_allowed_content_types: Vec<String> = attachment_allowed_content_types_str
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let compression_min_size_bytes = env::var("COMPRESSION_MIN_SIZE_BYTES")
+            .unwrap_or_else(|_| "1024".to_string())
+            .parse()
+            .unwrap_or(1024);
+
         Ok(Config {
             database_url,
             server_port,
-            api_key,
             allowed_origins,
             rate_limit_per_minute,
             enable_tls,
             tls_cert_path,
             tls_key_path,
-            shared_api_rate_limit_per_second,
-            shared_api_rate_limit_burst,
+            tls_client_ca_path,
+            shared_api_tls_port,
+            enable_acme,
+            acme_directory_url,
+            acme_domain,
+            acme_contact_email,
+            acme_state_dir,
+            shared_api_requests_per_minute,
+            shared_api_bulk_requests_per_hour,
+            shared_api_anomaly_distinct_ids_threshold,
+            shared_api_anomaly_window_secs,
+            shared_api_anomaly_not_found_threshold,
+            jwt_secret,
+            jwt_audience,
+            legacy_api_key_enabled,
+            sync_shared_secret,
+            attachment_storage_dir,
+            attachment_max_size_bytes,
+            attachment_allowed_content_types,
+            compression_min_size_bytes,
         })
     }
 }
\ No newline at end of file
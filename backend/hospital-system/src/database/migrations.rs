@@ -0,0 +1,36 @@
+use sqlx::PgPool;
+use sqlx::migrate::Migrator;
+
+/// Embeds the SQL files under `migrations/` into the binary so fresh
+/// deployments and CI don't depend on a schema being applied out of band.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Runs any pending migrations against `pool`.
+///
+/// Call this once, right after the connection pool is established. Fails
+/// fast with a clear log line if a migration cannot be applied, since a
+/// half-migrated schema is worse than a server that refuses to start.
+///
+/// Read-only replicas should not attempt migrations - set
+/// `SKIP_AUTO_MIGRATIONS=true` to have the caller skip this step entirely.
+pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    log::info!("Running database migrations...");
+
+    MIGRATOR.run(pool).await.map_err(|e| {
+        log::error!("❌ Failed to run database migrations: {}", e);
+        e
+    })?;
+
+    log::info!("✅ Database migrations up to date");
+    Ok(())
+}
+
+/// Returns true if automatic migrations should be skipped (e.g. on a
+/// read-only replica), based on the `SKIP_AUTO_MIGRATIONS` environment
+/// variable.
+pub fn should_skip_migrations() -> bool {
+    std::env::var("SKIP_AUTO_MIGRATIONS")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false)
+}
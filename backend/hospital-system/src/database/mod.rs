@@ -1,16 +1,33 @@
 pub mod connection;
 pub mod queries;
+pub mod migrations;
 
 // Re-export connection function
 pub use connection::establish_connection;
 
+// Re-export migration helpers
+pub use migrations::{run_migrations, should_skip_migrations};
+
 // Re-export all query functions
 pub use queries::{
     get_all_patients,
+    get_patients_page,
     get_patient_by_id,
     get_patient_by_personal_id,
     create_patient,
     update_patient,
     delete_patient,
     get_flagged_patients,
+    apply_flag_sync,
+    get_user_by_username,
+    create_attachment,
+    get_attachments_by_patient,
+    get_attachment_by_id,
+    create_access_request,
+    get_access_request_by_id,
+    approve_access_request,
+    deny_access_request,
+    check_access,
+    auto_approve_lapsed,
+    expire_stale_approved,
 };
\ No newline at end of file
@@ -1,5 +1,8 @@
 use sqlx::PgPool;
-use crate::models::{Patient, CreatePatient, UpdatePatient};
+use crate::models::{
+    CreateAccessRequest, CreatePatient, Patient, SharedAccessRequest, UpdatePatient, User,
+    Attachment,
+};
 
 /// Retrieves all patients from the database
 /// 
@@ -21,6 +24,34 @@ pub async fn get_all_patients(pool: &PgPool) -> Result<Vec<Patient>, sqlx::Error
     Ok(patients)
 }
 
+/// Retrieves up to `limit` patients with `id > after_id`, ordered by `id`
+///
+/// Keyset (cursor) pagination for the shared API: the caller passes back the
+/// last `id` it saw as `after_id` to resume where it left off, instead of
+/// the whole table being serialized in one response.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `limit` - Maximum number of rows to return
+/// * `after_id` - Only rows with `id` greater than this are returned
+///
+/// # Returns
+///
+/// * `Result<Vec<Patient>, sqlx::Error>` - Up to `limit` patients
+pub async fn get_patients_page(pool: &PgPool, limit: i64, after_id: i32) -> Result<Vec<Patient>, sqlx::Error> {
+    let patients = sqlx::query_as!(
+        Patient,
+        "SELECT id, full_name, personal_id, flag FROM patients WHERE id > $1 ORDER BY id LIMIT $2",
+        after_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(patients)
+}
+
 /// Retrieves a patient by their database ID
 /// 
 /// # Arguments
@@ -184,6 +215,287 @@ pub async fn get_flagged_patients(pool: &PgPool) -> Result<Vec<Patient>, sqlx::E
     Ok(flagged_patients)
 }
 
+/// Retrieves a user account by username, for password verification during
+/// `/auth/login`.
+pub async fn get_user_by_username(pool: &PgPool, username: &str) -> Result<Option<User>, sqlx::Error> {
+    let user = sqlx::query_as!(
+        User,
+        "SELECT id, username, password_hash, roles, created_at FROM users WHERE username = $1",
+        username
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(user)
+}
+
+/// Applies an inbound flag-sync event from the police system, deduping on
+/// `(source, sequence)` so a retried delivery never double-applies a flag
+/// change.
+///
+/// # Returns
+///
+/// * `Ok(true)` if the flag was applied (first time this sequence was seen)
+/// * `Ok(false)` if this `(source, sequence)` pair was already applied
+pub async fn apply_flag_sync(
+    pool: &PgPool,
+    source: &str,
+    sequence: i64,
+    personal_id: &str,
+    flag: bool,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let inserted = sqlx::query!(
+        "INSERT INTO sync_inbox (source, sequence) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        source,
+        sequence
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if inserted {
+        sqlx::query!(
+            "UPDATE patients SET flag = $1 WHERE personal_id = $2",
+            flag,
+            personal_id
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(inserted)
+}
+
+/// Records metadata for an attachment already streamed to disk by
+/// [`crate::attachments::save_multipart_field`].
+#[allow(clippy::too_many_arguments)]
+pub async fn create_attachment(
+    pool: &PgPool,
+    patient_id: i32,
+    filename: &str,
+    content_type: &str,
+    size_bytes: i64,
+    sha256: &str,
+    storage_path: &str,
+) -> Result<Attachment, sqlx::Error> {
+    let attachment = sqlx::query_as!(
+        Attachment,
+        "INSERT INTO patient_attachments (patient_id, filename, content_type, size_bytes, sha256, storage_path)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id, patient_id, filename, content_type, size_bytes, sha256, storage_path, created_at",
+        patient_id,
+        filename,
+        content_type,
+        size_bytes,
+        sha256,
+        storage_path
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(attachment)
+}
+
+/// Lists attachment metadata for a patient, most recent first.
+pub async fn get_attachments_by_patient(
+    pool: &PgPool,
+    patient_id: i32,
+) -> Result<Vec<Attachment>, sqlx::Error> {
+    let attachments = sqlx::query_as!(
+        Attachment,
+        "SELECT id, patient_id, filename, content_type, size_bytes, sha256, storage_path, created_at
+         FROM patient_attachments
+         WHERE patient_id = $1
+         ORDER BY created_at DESC",
+        patient_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(attachments)
+}
+
+/// Retrieves a single attachment by ID, for download.
+pub async fn get_attachment_by_id(pool: &PgPool, id: i32) -> Result<Option<Attachment>, sqlx::Error> {
+    let attachment = sqlx::query_as!(
+        Attachment,
+        "SELECT id, patient_id, filename, content_type, size_bytes, sha256, storage_path, created_at
+         FROM patient_attachments
+         WHERE id = $1",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(attachment)
+}
+
+/// Creates a break-glass access request in the `Requested` state.
+pub async fn create_access_request(
+    pool: &PgPool,
+    requester: &str,
+    request: CreateAccessRequest,
+) -> Result<SharedAccessRequest, sqlx::Error> {
+    let wait_time_days = request
+        .wait_time_days
+        .unwrap_or(crate::models::shared_access::DEFAULT_WAIT_TIME_DAYS);
+
+    let created = sqlx::query_as!(
+        SharedAccessRequest,
+        "INSERT INTO shared_access (requester, personal_id, access_type, wait_time_days)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id, requester, personal_id, access_type, status,
+                   wait_time_days, recovery_initiated_at, decided_at, decided_by,
+                   last_notification_at, created_at",
+        requester,
+        request.personal_id,
+        request.access_type.as_str(),
+        wait_time_days
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(created)
+}
+
+/// Retrieves a single access request by ID.
+pub async fn get_access_request_by_id(
+    pool: &PgPool,
+    id: i32,
+) -> Result<Option<SharedAccessRequest>, sqlx::Error> {
+    let request = sqlx::query_as!(
+        SharedAccessRequest,
+        "SELECT id, requester, personal_id, access_type, status,
+                wait_time_days, recovery_initiated_at, decided_at, decided_by,
+                last_notification_at, created_at
+         FROM shared_access WHERE id = $1",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(request)
+}
+
+/// Approves a `Requested` access request. Returns `None` if the request
+/// doesn't exist or has already left the `Requested` state.
+pub async fn approve_access_request(
+    pool: &PgPool,
+    id: i32,
+    decided_by: &str,
+) -> Result<Option<SharedAccessRequest>, sqlx::Error> {
+    let approved = sqlx::query_as!(
+        SharedAccessRequest,
+        "UPDATE shared_access
+         SET status = 'Approved', decided_at = now(), decided_by = $1
+         WHERE id = $2 AND status = 'Requested'
+         RETURNING id, requester, personal_id, access_type, status,
+                   wait_time_days, recovery_initiated_at, decided_at, decided_by,
+                   last_notification_at, created_at",
+        decided_by,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(approved)
+}
+
+/// Denies an access request that hasn't yet expired. A `Requested` request
+/// is stopped before it can auto-approve; an already-`Approved` grant can
+/// also be revoked this way.
+pub async fn deny_access_request(
+    pool: &PgPool,
+    id: i32,
+    decided_by: &str,
+) -> Result<Option<SharedAccessRequest>, sqlx::Error> {
+    let denied = sqlx::query_as!(
+        SharedAccessRequest,
+        "UPDATE shared_access
+         SET status = 'Denied', decided_at = now(), decided_by = $1
+         WHERE id = $2 AND status IN ('Requested', 'Approved')
+         RETURNING id, requester, personal_id, access_type, status,
+                   wait_time_days, recovery_initiated_at, decided_at, decided_by,
+                   last_notification_at, created_at",
+        decided_by,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(denied)
+}
+
+/// Returns true if there's an `Approved` grant covering `personal_id` -
+/// either a grant naming that exact `personal_id`, or a blanket "all" grant
+/// (`personal_id IS NULL`). Pass `None` to check only for a blanket grant,
+/// as the list/flagged endpoints do.
+pub async fn check_access(pool: &PgPool, personal_id: Option<&str>) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT EXISTS(
+            SELECT 1 FROM shared_access
+            WHERE status = 'Approved' AND (personal_id IS NULL OR personal_id = $1)
+         ) AS \"granted!\"",
+        personal_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.granted)
+}
+
+/// Flips every `Requested` row whose `wait_time_days` has elapsed with no
+/// denial over to `Approved`, stamping `last_notification_at` so the sweep
+/// task can tell which rows it just changed. Returns the updated rows so
+/// the caller can emit one audit entry per auto-approval.
+pub async fn auto_approve_lapsed(pool: &PgPool) -> Result<Vec<SharedAccessRequest>, sqlx::Error> {
+    let approved = sqlx::query_as!(
+        SharedAccessRequest,
+        "UPDATE shared_access
+         SET status = 'Approved', decided_at = now(), decided_by = 'system:auto-approve',
+             last_notification_at = now()
+         WHERE status = 'Requested'
+           AND recovery_initiated_at + (wait_time_days || ' days')::interval <= now()
+         RETURNING id, requester, personal_id, access_type, status,
+                   wait_time_days, recovery_initiated_at, decided_at, decided_by,
+                   last_notification_at, created_at"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(approved)
+}
+
+/// Expires every `Approved` grant older than `ttl_days` since it was
+/// decided, stamping `last_notification_at`. Returns the updated rows so
+/// the caller can emit one audit entry per expiry.
+pub async fn expire_stale_approved(
+    pool: &PgPool,
+    ttl_days: i32,
+) -> Result<Vec<SharedAccessRequest>, sqlx::Error> {
+    let expired = sqlx::query_as!(
+        SharedAccessRequest,
+        "UPDATE shared_access
+         SET status = 'Expired', last_notification_at = now()
+         WHERE status = 'Approved'
+           AND decided_at IS NOT NULL
+           AND decided_at + ($1 || ' days')::interval <= now()
+         RETURNING id, requester, personal_id, access_type, status,
+                   wait_time_days, recovery_initiated_at, decided_at, decided_by,
+                   last_notification_at, created_at",
+        ttl_days
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(expired)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,4 +538,30 @@ mod tests {
         let flagged = get_flagged_patients(&pool).await.unwrap();
         assert!(flagged.iter().all(|p| p.flag));
     }
+
+    #[tokio::test]
+    #[ignore] // Requires database to be running
+    async fn test_access_request_lifecycle() {
+        dotenv::dotenv().ok();
+        let pool = crate::database::connection::establish_connection()
+            .await
+            .expect("Failed to connect to database");
+
+        let request = CreateAccessRequest {
+            personal_id: Some("19990101-1234".to_string()),
+            access_type: crate::models::AccessType::View,
+            wait_time_days: Some(0),
+        };
+
+        let created = create_access_request(&pool, "police-system", request).await.unwrap();
+        assert_eq!(created.status, "Requested");
+        assert!(!check_access(&pool, Some("19990101-1234")).await.unwrap());
+
+        let approved = approve_access_request(&pool, created.id, "staff_user")
+            .await
+            .unwrap()
+            .expect("request should still be Requested");
+        assert_eq!(approved.status, "Approved");
+        assert!(check_access(&pool, Some("19990101-1234")).await.unwrap());
+    }
 }
\ No newline at end of file
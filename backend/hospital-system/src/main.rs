@@ -3,14 +3,25 @@ mod database;
 mod models;
 mod middleware;
 mod config;
+mod tls;
+mod utils;
+mod sync;
+mod attachments;
+mod openapi;
+mod shared_access;
 
 use actix_web::{web, App, HttpServer, middleware as actix_middleware};
 use actix_cors::Cors;
 use config::Config;
 use std::fs::File;
 use std::io::BufReader;
-use rustls::{Certificate, PrivateKey, ServerConfig};
+use std::path::PathBuf;
+use rustls::{server::AllowAnyAuthenticatedClient, Certificate, PrivateKey, RootCertStore, ServerConfig};
 use rustls_pemfile::{certs, pkcs8_private_keys};
+use std::sync::Arc;
+use tls::{CertManager, ClientCertInfo};
+use config::{JwtSecret, JwtAudience, SyncSharedSecret};
+use attachments::AttachmentConfig;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -30,6 +41,7 @@ async fn main() -> std::io::Result<()> {
     log::info!("   - API Key authentication: ENABLED");
     log::info!("   - Rate limiting: {} req/min", config.rate_limit_per_minute);
     log::info!("   - TLS: {}", if config.enable_tls { "ENABLED" } else { "DISABLED (dev only)" });
+    log::info!("   - Mutual TLS (client certs) on /api/shared: {}", if config.enable_tls && !config.enable_acme && config.tls_client_ca_path.is_some() { "REQUIRED, on its own listener" } else { "disabled" });
     
     if !config.enable_tls {
         log::warn!("⚠️  TLS is DISABLED - This is only acceptable in development!");
@@ -45,112 +57,409 @@ async fn main() -> std::io::Result<()> {
         .expect("Failed to create database connection pool");
     
     log::info!("✅ Database connection established");
-    
+
+    if database::should_skip_migrations() {
+        log::warn!("⚠️  SKIP_AUTO_MIGRATIONS is set - skipping automatic migrations (expected on read-only replicas)");
+    } else {
+        database::run_migrations(&pool)
+            .await
+            .expect("Failed to run database migrations");
+    }
+
+    // Sweep break-glass access requests: auto-approve lapsed `Requested`
+    // rows and expire stale `Approved` grants.
+    shared_access::spawn_access_sweep_task(pool.clone());
+    log::info!("🚨 Break-glass access sweep task started");
+
     // Log available routes
     log::info!("📋 Configuring routes:");
-    log::info!("   - GET    /patients (Internal)");
-    log::info!("   - POST   /patients (Internal)");
-    log::info!("   - GET    /patients/{{id}} (Internal)");
-    log::info!("   - PUT    /patients/{{id}} (Internal)");
-    log::info!("   - DELETE /patients/{{id}} (Internal)");
-    log::info!("   - GET    /patients/personal/{{personal_id}} (Internal)");
-    log::info!("   - GET    /patients/flagged (Internal)");
+    log::info!("   - GET    /patients (requires read_patients scope)");
+    log::info!("   - POST   /patients (requires write_patients scope + staff session)");
+    log::info!("   - GET    /patients/{{id}} (requires read_patients scope)");
+    log::info!("   - PUT    /patients/{{id}} (requires write_patients scope)");
+    log::info!("   - DELETE /patients/{{id}} (requires write_patients scope)");
+    log::info!("   - GET    /patients/personal/{{personal_id}} (requires read_patients scope)");
+    log::info!("   - GET    /patients/flagged (requires view_flagged scope)");
+    log::info!("   - POST   /patients/{{id}}/attachments (requires write_patients scope)");
+    log::info!("   - GET    /patients/{{id}}/attachments (requires read_patients scope)");
+    log::info!("   - GET    /attachments/{{id}} (requires read_patients scope)");
     log::info!("   - GET    /api/shared/patients (Authenticated)");
     log::info!("   - GET    /api/shared/patients/flagged (Authenticated)");
     log::info!("   - GET    /api/shared/patients/{{personal_id}} (Authenticated)");
-    
-    log::info!("🔒 API Key authentication required for /api/shared/* endpoints");
-    
-    let api_key = config.api_key.clone();
+    log::info!("   - POST   /api/shared/access-requests (Authenticated; creates a break-glass access request)");
+    log::info!("   - POST   /shared-access/{{id}}/approve (requires write_patients scope + staff session)");
+    log::info!("   - POST   /shared-access/{{id}}/deny (requires write_patients scope + staff session)");
+
+    log::info!("   - POST   /auth/token (Service authentication)");
+    log::info!("   - POST   /auth/login (User session)");
+    log::info!("   - POST   /auth/refresh (User session renewal)");
+    log::info!("   - POST   /api/shared/sync/flag (HMAC-signed, from police-system)");
+    log::info!("   - GET    /api-docs/openapi.json (generated API spec, unauthenticated)");
+
+    log::info!("🔒 JWT bearer token required for /api/shared/* endpoints");
+    if config.legacy_api_key_enabled {
+        log::warn!("⚠️  Legacy API key fallback is still ENABLED for /api/shared/* - disable via LEGACY_API_KEY_ENABLED once consumers migrate");
+    }
+
+    // Per-key scopes for /patients/* - lets the police integration hold a
+    // key scoped to `view_flagged` only, instead of sharing the same
+    // all-or-nothing API_KEY every internal caller uses.
+    let auth_config = middleware::AuthConfig::from_env()
+        .expect("Failed to load PATIENT_API_KEYS");
+    log::info!("🔒 Scope-based API key authorization required for /patients/* endpoints");
+
+    // Named, scoped, revocable partner credentials for /api/shared/* - the
+    // legacy single-key fallback now resolves against this registry instead
+    // of one shared secret, so a leaked partner key can be revoked (edit
+    // SHARED_API_KEYS, send SIGHUP) without invalidating every other partner.
+    let shared_api_keys = Arc::new(
+        middleware::ApiKeyRegistry::from_env().expect("Failed to load SHARED_API_KEYS"),
+    );
+    middleware::spawn_api_key_reload_task(shared_api_keys.clone());
+    log::info!("🔒 Shared API partner keys loaded; SIGHUP reloads SHARED_API_KEYS");
+
+    // Per-client (not per-IP) rate limiting and anomaly flagging for
+    // /api/shared - a single partner calling from many addresses gets one
+    // bucket, keyed by the identity ApiKeyAuth/JwtAuth already resolved.
+    let shared_api_rate_limiter = middleware::SharedApiRateLimiter::new(middleware::SharedApiLimits {
+        requests_per_minute: config.shared_api_requests_per_minute,
+        bulk_requests_per_hour: config.shared_api_bulk_requests_per_hour,
+        anomaly_distinct_ids_threshold: config.shared_api_anomaly_distinct_ids_threshold,
+        anomaly_window_secs: config.shared_api_anomaly_window_secs,
+        anomaly_not_found_threshold: config.shared_api_anomaly_not_found_threshold,
+    });
+    log::info!(
+        "🔒 Shared API rate limits: {}/min, {} bulk-list calls/hour per client",
+        config.shared_api_requests_per_minute,
+        config.shared_api_bulk_requests_per_hour
+    );
+
     let allowed_origins = config.allowed_origins.clone();
     let enable_tls = config.enable_tls;
-    
-    // Create HTTP server
-    let server = HttpServer::new(move || {
-        // Create rate limiter for each worker
-        let rate_limiter = middleware::configure_rate_limiter(config.rate_limit_per_minute);
-        
-        // Configure CORS - STRICT production settings
-        let mut cors = Cors::default()
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-            .allowed_headers(vec![
-                actix_web::http::header::CONTENT_TYPE,
-                actix_web::http::header::AUTHORIZATION,
-                actix_web::http::header::HeaderName::from_static("x-api-key"),
-            ])
-            .max_age(3600);
-        
-        // Only allow specific origins (no wildcard)
-        for origin in &allowed_origins {
-            cors = cors.allowed_origin(origin);
-        }
-        
-        App::new()
-            // Add security middleware
-            .wrap(actix_middleware::Logger::default())
-            .wrap(cors)
-            .wrap(rate_limiter)
-            
-            // Add security headers
-            .wrap(actix_middleware::DefaultHeaders::new()
-                .add(("X-Content-Type-Options", "nosniff"))
-                .add(("X-Frame-Options", "DENY"))
-                .add(("X-XSS-Protection", "1; mode=block"))
-                .add(("Strict-Transport-Security", "max-age=31536000; includeSubDomains"))
-            )
-            
-            // Share database pool across all handlers
-            .app_data(web::Data::new(pool.clone()))
-            
-            // Configure API routes
-            .configure(api::configure_patients)
-            
-            // Shared API routes with authentication
-            .service(
-                web::scope("/api/shared")
-                    .wrap(middleware::ApiKeyAuth::new(api_key.clone()))
-                    .configure(api::configure_shared)
-            )
-            
-            // Health check endpoint
-            .route("/health", web::get().to(health_check))
-    });
-    
-    // Bind server with or without TLS
-    if enable_tls {
-        log::info!("🔐 TLS enabled - loading certificates...");
-        
-        let tls_config = load_tls_config(&config)?;
-        
+    let jwt_secret = config.jwt_secret.clone();
+    let jwt_audience = config.jwt_audience.clone();
+    let legacy_api_key_enabled = config.legacy_api_key_enabled;
+    let rate_limit_per_minute = config.rate_limit_per_minute;
+    let acme_state_dir = config.acme_state_dir.clone();
+    let sync_shared_secret = config.sync_shared_secret.clone();
+    let compression_min_size_bytes = config.compression_min_size_bytes;
+    let attachment_config = AttachmentConfig {
+        storage_dir: config.attachment_storage_dir.clone(),
+        max_size_bytes: config.attachment_max_size_bytes,
+        allowed_content_types: config.attachment_allowed_content_types.clone(),
+    };
+    log::info!(
+        "📎 Patient attachments: storage_dir='{}', max_size={} bytes, allowed_content_types={:?}",
+        attachment_config.storage_dir,
+        attachment_config.max_size_bytes,
+        attachment_config.allowed_content_types
+    );
+    log::info!(
+        "🗜️  Response compression: gzip/deflate for bodies >= {} bytes",
+        compression_min_size_bytes
+    );
+
+    // /api/shared is split onto its own listener, with its own
+    // client-cert-required TLS config, whenever mutual TLS is configured for
+    // it - a single shared `ServerConfig` would otherwise force EVERY
+    // connection (including /patients, /auth/login, /health and the ACME
+    // challenge) through the same client-certificate requirement meant only
+    // for the inter-organization police link. ACME-provisioned certs don't
+    // support a custom client verifier, so the split only applies to the
+    // statically-configured certificate path.
+    let split_shared_api_listener = enable_tls && !config.enable_acme && config.tls_client_ca_path.is_some();
+    if config.enable_acme && config.tls_client_ca_path.is_some() {
+        log::warn!("⚠️  TLS_CLIENT_CA_PATH is set but ENABLE_ACME=true - mutual TLS for /api/shared is not supported with automatic ACME certificates; /api/shared will run without requiring a client certificate");
+    }
+    let shared_api_tls_address = format!("127.0.0.1:{}", config.shared_api_tls_port);
+
+    if split_shared_api_listener {
+        log::info!("🔐 TLS enabled - loading certificates from static files...");
+        let main_tls_config = load_tls_config(&config)?;
+        let shared_tls_config = build_shared_api_tls_config(&config)?;
+
+        // Both listeners' worker closures run `move` and need their own
+        // copy of anything they capture - clone before the first closure
+        // consumes the outer binding.
+        let shared_pool = pool.clone();
+        let shared_jwt_secret = jwt_secret.clone();
+        let shared_jwt_audience = jwt_audience.clone();
+        let shared_listener_api_keys = shared_api_keys.clone();
+        let shared_listener_rate_limiter = shared_api_rate_limiter.clone();
+
+        let main_server = HttpServer::new(move || {
+            let rate_limiter = middleware::configure_rate_limiter(rate_limit_per_minute);
+
+            let mut cors = Cors::default()
+                .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+                .allowed_headers(vec![
+                    actix_web::http::header::CONTENT_TYPE,
+                    actix_web::http::header::AUTHORIZATION,
+                    actix_web::http::header::HeaderName::from_static("x-api-key"),
+                ])
+                .max_age(3600);
+
+            for origin in &allowed_origins {
+                cors = cors.allowed_origin(origin);
+            }
+
+            App::new()
+                .wrap(actix_middleware::Logger::default())
+                .wrap(cors)
+                .wrap(rate_limiter)
+                .wrap(middleware::ResponseCompression::new(compression_min_size_bytes))
+                .wrap(actix_middleware::DefaultHeaders::new()
+                    .add(("X-Content-Type-Options", "nosniff"))
+                    .add(("X-Frame-Options", "DENY"))
+                    .add(("X-XSS-Protection", "1; mode=block"))
+                    .add(("Strict-Transport-Security", "max-age=31536000; includeSubDomains"))
+                )
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(JwtSecret(jwt_secret.clone())))
+                .app_data(web::Data::new(JwtAudience(jwt_audience.clone())))
+                .app_data(web::Data::new(SyncSharedSecret(sync_shared_secret.clone())))
+                .app_data(web::Data::new(auth_config.clone()))
+                .app_data(web::Data::new(attachment_config.clone()))
+                .app_data(web::Data::new(shared_api_rate_limiter.clone()))
+                .configure(api::configure_patients)
+                .configure(api::configure_auth)
+                .configure(api::configure_sync)
+                .configure(api::configure_attachments)
+                .configure(api::configure_shared_access)
+                .configure(openapi::configure_openapi)
+                .route("/health", web::get().to(health_check))
+                .route(
+                    "/.well-known/acme-challenge/{token}",
+                    web::get().to({
+                        let acme_state_dir = acme_state_dir.clone();
+                        move |token: web::Path<String>| {
+                            let acme_state_dir = acme_state_dir.clone();
+                            async move { serve_acme_challenge(acme_state_dir, token.into_inner()) }
+                        }
+                    }),
+                )
+        })
+        .on_connect(extract_client_cert_info)
+        .bind_rustls_021(&server_address, main_tls_config)
+        .map_err(|e| {
+            log::error!("❌ Failed to bind HTTPS server to {}: {}", server_address, e);
+            e
+        })?
+        .run();
+
+        let shared_server = HttpServer::new(move || {
+            let rate_limiter = middleware::configure_rate_limiter(rate_limit_per_minute);
+
+            let mut jwt_auth = middleware::JwtAuth::new(shared_jwt_secret.clone(), shared_jwt_audience.clone())
+                .require_scope("read:patients");
+            if legacy_api_key_enabled {
+                jwt_auth = jwt_auth.with_legacy_api_keys(shared_listener_api_keys.clone());
+            }
+
+            App::new()
+                .wrap(actix_middleware::Logger::default())
+                .wrap(rate_limiter)
+                .wrap(middleware::ResponseCompression::new(compression_min_size_bytes))
+                .wrap(actix_middleware::DefaultHeaders::new()
+                    .add(("X-Content-Type-Options", "nosniff"))
+                    .add(("X-Frame-Options", "DENY"))
+                    .add(("X-XSS-Protection", "1; mode=block"))
+                    .add(("Strict-Transport-Security", "max-age=31536000; includeSubDomains"))
+                )
+                .app_data(web::Data::new(shared_pool.clone()))
+                // Shared API routes - JWT bearer token required, with legacy
+                // partner keys from SHARED_API_KEYS still accepted behind
+                // LEGACY_API_KEY_ENABLED while consumers migrate to /auth/token
+                .service(
+                    web::scope("/api/shared")
+                        // Registered before jwt_auth below, so it ends up as
+                        // the inner layer - it runs after JwtAuth has
+                        // already resolved and attached the caller's identity.
+                        .wrap(middleware::SharedRateLimit::new(shared_listener_rate_limiter.clone()))
+                        .wrap(jwt_auth)
+                        .configure(api::configure_shared)
+                )
+        })
+        .on_connect(extract_client_cert_info)
+        .bind_rustls_021(&shared_api_tls_address, shared_tls_config)
+        .map_err(|e| {
+            log::error!("❌ Failed to bind HTTPS /api/shared listener to {}: {}", shared_api_tls_address, e);
+            e
+        })?
+        .run();
+
         log::info!("🚀 Starting HTTPS server at https://{}", server_address);
-        
-        server
-            .bind_rustls_021(&server_address, tls_config)
-            .map_err(|e| {
-                log::error!("❌ Failed to bind HTTPS server to {}: {}", server_address, e);
-                e
-            })?
-            .run()
-            .await?;
+        log::info!("🔒 Starting HTTPS /api/shared listener at https://{} (client certificate required)", shared_api_tls_address);
+
+        futures_util::try_join!(main_server, shared_server)?;
     } else {
-        log::info!("🚀 Starting HTTP server at http://{}", server_address);
-        
-        server
-            .bind(&server_address)
-            .map_err(|e| {
-                log::error!("❌ Failed to bind HTTP server to {}: {}", server_address, e);
-                e
-            })?
-            .run()
-            .await?;
+        // Create HTTP server
+        let server = HttpServer::new(move || {
+            // Create rate limiter for each worker
+            let rate_limiter = middleware::configure_rate_limiter(rate_limit_per_minute);
+
+            // Configure CORS - STRICT production settings
+            let mut cors = Cors::default()
+                .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+                .allowed_headers(vec![
+                    actix_web::http::header::CONTENT_TYPE,
+                    actix_web::http::header::AUTHORIZATION,
+                    actix_web::http::header::HeaderName::from_static("x-api-key"),
+                ])
+                .max_age(3600);
+
+            // Only allow specific origins (no wildcard)
+            for origin in &allowed_origins {
+                cors = cors.allowed_origin(origin);
+            }
+
+            App::new()
+                // Add security middleware
+                .wrap(actix_middleware::Logger::default())
+                .wrap(cors)
+                .wrap(rate_limiter)
+                .wrap(middleware::ResponseCompression::new(compression_min_size_bytes))
+
+                // Add security headers
+                .wrap(actix_middleware::DefaultHeaders::new()
+                    .add(("X-Content-Type-Options", "nosniff"))
+                    .add(("X-Frame-Options", "DENY"))
+                    .add(("X-XSS-Protection", "1; mode=block"))
+                    .add(("Strict-Transport-Security", "max-age=31536000; includeSubDomains"))
+                )
+
+                // Share database pool across all handlers
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(JwtSecret(jwt_secret.clone())))
+                .app_data(web::Data::new(JwtAudience(jwt_audience.clone())))
+                .app_data(web::Data::new(SyncSharedSecret(sync_shared_secret.clone())))
+                .app_data(web::Data::new(auth_config.clone()))
+                .app_data(web::Data::new(attachment_config.clone()))
+                .app_data(web::Data::new(shared_api_rate_limiter.clone()))
+
+                // Configure API routes
+                .configure(api::configure_patients)
+                .configure(api::configure_auth)
+                .configure(api::configure_sync)
+                .configure(api::configure_attachments)
+                .configure(api::configure_shared_access)
+                .configure(openapi::configure_openapi)
+
+                // Shared API routes - JWT bearer token required, with legacy
+                // partner keys from SHARED_API_KEYS still accepted behind
+                // LEGACY_API_KEY_ENABLED while consumers migrate to /auth/token
+                .service({
+                    let mut jwt_auth = middleware::JwtAuth::new(jwt_secret.clone(), jwt_audience.clone())
+                        .require_scope("read:patients");
+                    if legacy_api_key_enabled {
+                        jwt_auth = jwt_auth.with_legacy_api_keys(shared_api_keys.clone());
+                    }
+                    web::scope("/api/shared")
+                        // Registered before jwt_auth below, so it ends up as the
+                        // inner layer - it runs after JwtAuth has already
+                        // resolved and attached the caller's identity.
+                        .wrap(middleware::SharedRateLimit::new(shared_api_rate_limiter.clone()))
+                        .wrap(jwt_auth)
+                        .configure(api::configure_shared)
+                })
+
+                // Health check endpoint
+                .route("/health", web::get().to(health_check))
+                // ACME HTTP-01 challenge responses, served from CertManager's state dir
+                .route(
+                    "/.well-known/acme-challenge/{token}",
+                    web::get().to({
+                        let acme_state_dir = acme_state_dir.clone();
+                        move |token: web::Path<String>| {
+                            let acme_state_dir = acme_state_dir.clone();
+                            async move { serve_acme_challenge(acme_state_dir, token.into_inner()) }
+                        }
+                    }),
+                )
+        })
+        // Attach a verified client certificate's identity to the request
+        // extensions, if the connection carries one. This branch only runs
+        // when the dedicated mutual-TLS /api/shared listener is not in use,
+        // and neither TLS config below requests client certificates, so this
+        // is a no-op here for plain HTTP and TLS alike.
+        .on_connect(extract_client_cert_info);
+
+        // Bind server with or without TLS
+        if enable_tls {
+            let tls_config = if config.enable_acme {
+                log::info!("🔐 ACME enabled - obtaining/renewing certificate automatically...");
+
+                let (cert_manager, resolver) = CertManager::bootstrap(
+                    config.acme_directory_url.clone(),
+                    config.acme_domain.clone().expect("ACME_DOMAIN validated in Config::from_env"),
+                    config.acme_contact_email.clone().expect("ACME_CONTACT_EMAIL validated in Config::from_env"),
+                    PathBuf::from(&config.acme_state_dir),
+                )
+                .await
+                .expect("Failed to bootstrap ACME certificate manager");
+
+                cert_manager.spawn_renewal_task();
+
+                ServerConfig::builder()
+                    .with_safe_defaults()
+                    .with_no_client_auth()
+                    .with_cert_resolver(std::sync::Arc::new(resolver))
+            } else {
+                log::info!("🔐 TLS enabled - loading certificates from static files...");
+                load_tls_config(&config)?
+            };
+
+            log::info!("🚀 Starting HTTPS server at https://{}", server_address);
+
+            server
+                .bind_rustls_021(&server_address, tls_config)
+                .map_err(|e| {
+                    log::error!("❌ Failed to bind HTTPS server to {}: {}", server_address, e);
+                    e
+                })?
+                .run()
+                .await?;
+        } else {
+            log::info!("🚀 Starting HTTP server at http://{}", server_address);
+
+            server
+                .bind(&server_address)
+                .map_err(|e| {
+                    log::error!("❌ Failed to bind HTTP server to {}: {}", server_address, e);
+                    e
+                })?
+                .run()
+                .await?;
+        }
     }
-    
+
     log::info!("🛑 Hospital System shut down");
     Ok(())
 }
 
-/// Load TLS configuration from certificate and key files
-fn load_tls_config(config: &Config) -> std::io::Result<ServerConfig> {
+/// Extracts the verified mTLS client certificate's identity from an
+/// incoming connection and attaches it to the connection data (read by
+/// handlers with `HttpRequest::conn_data`), for
+/// listeners bound with a client-cert-requiring `ServerConfig`. Shared by
+/// every `HttpServer` this process runs - a no-op for plain HTTP and for
+/// TLS connections that didn't present a client certificate.
+fn extract_client_cert_info(connection: &dyn std::any::Any, extensions: &mut actix_web::dev::Extensions) {
+    if let Some(tls_stream) = connection
+        .downcast_ref::<tokio_rustls::server::TlsStream<actix_web::rt::net::TcpStream>>()
+    {
+        if let Some(peer_certs) = tls_stream.get_ref().1.peer_certificates() {
+            if let Some(info) = peer_certs.first().and_then(ClientCertInfo::from_leaf_certificate) {
+                log::info!("mTLS: accepted client certificate, subject='{}'", info.subject);
+                extensions.insert(info);
+            }
+        }
+    }
+}
+
+/// Loads the certificate chain and private key shared by both the main
+/// listener and (when mutual TLS is configured) the dedicated /api/shared
+/// listener.
+fn load_cert_and_key(config: &Config) -> std::io::Result<(Vec<Certificate>, PrivateKey)> {
     let cert_path = config.tls_cert_path.as_ref()
         .ok_or_else(|| {
             log::error!("TLS_CERT_PATH not configured");
@@ -226,8 +535,18 @@ fn load_tls_config(config: &Config) -> std::io::Result<ServerConfig> {
     
     let private_key = keys.remove(0);
     log::info!("✅ Loaded private key");
-    
-    // Build TLS configuration
+
+    Ok((cert_chain, private_key))
+}
+
+/// Builds the `ServerConfig` for the main listener (/patients, /auth/*,
+/// /health, the ACME challenge route) - plain TLS, no client certificate
+/// required. When `TLS_CLIENT_CA_PATH` is set, that CA is only used to
+/// build the dedicated /api/shared listener's `ServerConfig` (see
+/// `build_shared_api_tls_config`); it never applies here.
+fn load_tls_config(config: &Config) -> std::io::Result<ServerConfig> {
+    let (cert_chain, private_key) = load_cert_and_key(config)?;
+
     let tls_config = ServerConfig::builder()
         .with_safe_defaults()
         .with_no_client_auth()
@@ -236,12 +555,72 @@ fn load_tls_config(config: &Config) -> std::io::Result<ServerConfig> {
             log::error!("Failed to build TLS configuration: {}", e);
             std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
         })?;
-    
+
     log::info!("✅ TLS configuration loaded successfully");
-    
+
     Ok(tls_config)
 }
 
+/// Builds the `ServerConfig` for the dedicated /api/shared listener -
+/// requires a client certificate signed by `config.tls_client_ca_path`.
+/// Only called when `split_shared_api_listener` is true, i.e. that path is
+/// set and ACME is not in use.
+fn build_shared_api_tls_config(config: &Config) -> std::io::Result<ServerConfig> {
+    let (cert_chain, private_key) = load_cert_and_key(config)?;
+
+    let ca_path = config.tls_client_ca_path.as_ref()
+        .expect("build_shared_api_tls_config called without TLS_CLIENT_CA_PATH set");
+
+    log::info!("Loading client CA for mutual TLS from: {}", ca_path);
+
+    let ca_file = File::open(ca_path).map_err(|e| {
+        log::error!("Failed to open client CA file '{}': {}", ca_path, e);
+        e
+    })?;
+    let mut ca_reader = BufReader::new(ca_file);
+
+    let ca_certs = certs(&mut ca_reader).map_err(|e| {
+        log::error!("Failed to parse client CA file: {}", e);
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    })?;
+
+    let mut root_store = RootCertStore::empty();
+    for cert in ca_certs {
+        root_store.add(&Certificate(cert)).map_err(|e| {
+            log::error!("Failed to add client CA certificate to root store: {}", e);
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+        })?;
+    }
+
+    log::info!("🔒 Mutual TLS enabled for /api/shared - client certificates signed by this CA are required");
+
+    let tls_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(Arc::new(AllowAnyAuthenticatedClient::new(root_store)))
+        .with_single_cert(cert_chain, private_key)
+        .map_err(|e| {
+            log::error!("Failed to build /api/shared TLS configuration: {}", e);
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+        })?;
+
+    log::info!("✅ /api/shared TLS configuration loaded successfully");
+
+    Ok(tls_config)
+}
+
+/// Serves the key authorization for an ACME HTTP-01 challenge token, as
+/// written by [`tls::CertManager`] into `<acme_state_dir>/http-01/<token>`.
+fn serve_acme_challenge(acme_state_dir: String, token: String) -> actix_web::HttpResponse {
+    let path = std::path::Path::new(&acme_state_dir).join("http-01").join(&token);
+
+    match std::fs::read_to_string(&path) {
+        Ok(key_authorization) => actix_web::HttpResponse::Ok()
+            .content_type("text/plain")
+            .body(key_authorization),
+        Err(_) => actix_web::HttpResponse::NotFound().finish(),
+    }
+}
+
 /// Health check endpoint
 async fn health_check() -> actix_web::HttpResponse {
     actix_web::HttpResponse::Ok().json(serde_json::json!({
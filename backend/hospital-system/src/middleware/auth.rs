@@ -1,18 +1,181 @@
+use std::collections::HashSet;
+use std::env;
+use std::sync::{Arc, RwLock};
+
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpResponse, body::BoxBody,
+    Error, HttpMessage, HttpResponse,
+    body::BoxBody,
 };
+use constant_time_eq::constant_time_eq;
 use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
-use constant_time_eq::constant_time_eq;
 
+/// One partner's credential for the shared inter-system API: who they are,
+/// what they're allowed to call, and whether the key still works.
+///
+/// Unlike [`crate::middleware::AuthConfig`] (which maps a bare key to scopes
+/// for `/patients/*`), every credential here carries a `client_name` - the
+/// shared API's audit trail needs to say *which* partner read a record, not
+/// just that "a" valid key was presented.
+#[derive(Debug, Clone)]
+struct ApiKeyCredential {
+    client_name: String,
+    key: String,
+    enabled: bool,
+    scopes: HashSet<String>,
+}
+
+/// The identity resolved from a matched [`ApiKeyCredential`], stashed in
+/// request extensions by [`ApiKeyAuthMiddleware`] (and by the legacy-key
+/// fallback in [`crate::middleware::jwt_auth::JwtAuth`]) so handlers and
+/// [`crate::utils::audit::extract_actor_from_request`] can use the client
+/// name as the audit actor instead of a hash of the raw key.
+#[derive(Debug, Clone)]
+pub struct ApiKeyClient(pub String);
+
+/// Registry of every partner credential for the shared API, loaded from
+/// `SHARED_API_KEYS` and reloadable at runtime without a redeploy.
+///
+/// Format: `client:key:scope1+scope2,client2:key2:scope1`, with an optional
+/// trailing `:revoked` segment to disable a credential while keeping it
+/// configured (e.g. to revoke a leaked key without losing its audit history):
+/// `client:key:scope1+scope2:revoked`.
+///
+/// Held as `Arc<ApiKeyRegistry>` (not `Rc`, unlike most of this crate's
+/// per-worker middleware state) because [`spawn_reload_task`] reloads the
+/// single shared instance from a signal handler running outside any one
+/// `HttpServer` worker - every worker's clone must see the update.
+pub struct ApiKeyRegistry {
+    credentials: RwLock<Vec<ApiKeyCredential>>,
+}
+
+impl ApiKeyRegistry {
+    pub fn from_env() -> Result<Self, String> {
+        Ok(ApiKeyRegistry {
+            credentials: RwLock::new(Self::parse_env()?),
+        })
+    }
+
+    fn parse_env() -> Result<Vec<ApiKeyCredential>, String> {
+        let raw = env::var("SHARED_API_KEYS").unwrap_or_default();
+        let mut credentials = Vec::new();
+
+        for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let mut parts = entry.split(':');
+            let client_name = parts
+                .next()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("malformed SHARED_API_KEYS entry: '{}'", entry))?;
+            let key = parts
+                .next()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("malformed SHARED_API_KEYS entry: '{}'", entry))?;
+            let scopes_raw = parts
+                .next()
+                .ok_or_else(|| format!("malformed SHARED_API_KEYS entry: '{}'", entry))?;
+
+            let enabled = match parts.next() {
+                None => true,
+                Some("revoked") => false,
+                Some(other) => {
+                    return Err(format!(
+                        "unknown trailing marker '{}' in SHARED_API_KEYS entry: '{}'",
+                        other, entry
+                    ))
+                }
+            };
+
+            let scopes: HashSet<String> = scopes_raw
+                .split('+')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect();
+
+            credentials.push(ApiKeyCredential {
+                client_name: client_name.to_string(),
+                key: key.to_string(),
+                enabled,
+                scopes,
+            });
+        }
+
+        Ok(credentials)
+    }
+
+    /// Re-reads `SHARED_API_KEYS` and replaces the registry's contents in
+    /// place, so a revoked or rotated key takes effect on the next request
+    /// without restarting the process.
+    pub fn reload(&self) -> Result<(), String> {
+        let credentials = Self::parse_env()?;
+        *self.credentials.write().expect("ApiKeyRegistry lock poisoned") = credentials;
+        Ok(())
+    }
+
+    /// Constant-time-compares `presented` against every *enabled* credential
+    /// and returns the matched client's name and scopes. Revoked credentials
+    /// are skipped entirely, so a revoked key is indistinguishable from one
+    /// that was never issued.
+    pub(crate) fn authenticate(&self, presented: &str) -> Option<(String, HashSet<String>)> {
+        let credentials = self.credentials.read().expect("ApiKeyRegistry lock poisoned");
+        credentials
+            .iter()
+            .filter(|c| c.enabled)
+            .find(|c| constant_time_eq(presented.as_bytes(), c.key.as_bytes()))
+            .map(|c| (c.client_name.clone(), c.scopes.clone()))
+    }
+}
+
+/// Spawns a background task that reloads `registry` from `SHARED_API_KEYS`
+/// on every `SIGHUP` - one background task per shared resource, the same
+/// shape as `tls::CertManager::spawn_renewal_task` (which runs on a timer
+/// rather than a signal). Lets an operator
+/// revoke or rotate a partner's key by editing the environment and sending
+/// `SIGHUP`, without redeploying and without disturbing any other client's key.
+pub fn spawn_reload_task(registry: Arc<ApiKeyRegistry>) {
+    tokio::spawn(async move {
+        let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(signal) => signal,
+            Err(e) => {
+                log::error!("Failed to install SIGHUP handler for SHARED_API_KEYS reload: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            hangup.recv().await;
+            match registry.reload() {
+                Ok(()) => log::info!("Reloaded SHARED_API_KEYS registry on SIGHUP"),
+                Err(e) => log::error!("Failed to reload SHARED_API_KEYS on SIGHUP: {}", e),
+            }
+        }
+    });
+}
+
+/// Standalone `X-API-Key` authentication middleware, backed by an
+/// [`ApiKeyRegistry`] instead of the single static key this type used to
+/// hold. Currently unused directly - `/api/shared` authenticates via
+/// [`crate::middleware::jwt_auth::JwtAuth`], which consults the same
+/// registry as its legacy-key fallback - but kept available for a scope
+/// that wants API-key auth without the JWT bearer path.
 pub struct ApiKeyAuth {
-    api_key: String,
+    registry: Arc<ApiKeyRegistry>,
+    required_scope: Option<Arc<String>>,
 }
 
 impl ApiKeyAuth {
-    pub fn new(api_key: String) -> Self {
-        Self { api_key }
+    pub fn new(registry: Arc<ApiKeyRegistry>) -> Self {
+        Self {
+            registry,
+            required_scope: None,
+        }
+    }
+
+    /// Require the matched client's scopes to contain `scope`
+    pub fn require_scope(mut self, scope: impl Into<String>) -> Self {
+        self.required_scope = Some(Arc::new(scope.into()));
+        self
     }
 }
 
@@ -31,14 +194,16 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(ApiKeyAuthMiddleware {
             service,
-            api_key: self.api_key.clone(),
+            registry: self.registry.clone(),
+            required_scope: self.required_scope.clone(),
         }))
     }
 }
 
 pub struct ApiKeyAuthMiddleware<S> {
     service: S,
-    api_key: String,
+    registry: Arc<ApiKeyRegistry>,
+    required_scope: Option<Arc<String>>,
 }
 
 impl<S, B> Service<ServiceRequest> for ApiKeyAuthMiddleware<S>
@@ -54,49 +219,59 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let api_key = self.api_key.clone();
-        
-        // Check if request has API key header
-        let provided_key = req.headers()
+        let provided_key = req
+            .headers()
             .get("X-API-Key")
             .and_then(|h| h.to_str().ok())
             .map(|s| s.to_string());
-        
-        match provided_key {
-            Some(key) if constant_time_eq(key.as_bytes(), api_key.as_bytes()) => {
-                // Valid API key - proceed with request
-                let fut = self.service.call(req);
-                Box::pin(async move {
-                    let res = fut.await?;
-                    Ok(res.map_into_boxed_body())
-                })
-            }
-            Some(_) => {
-                // Invalid API key
-                log::warn!("Invalid API key provided from IP: {:?}", req.peer_addr());
-                Box::pin(async move {
-                    Ok(req.into_response(
-                        HttpResponse::Unauthorized()
-                            .json(serde_json::json!({
-                                "error": "Invalid API key"
-                            }))
-                            .map_into_boxed_body()
-                    ))
-                })
-            }
-            None => {
-                // Missing API key
-                log::warn!("Missing API key from IP: {:?}", req.peer_addr());
-                Box::pin(async move {
+
+        let Some(key) = provided_key else {
+            log::warn!("Missing API key from IP: {:?}", req.peer_addr());
+            return Box::pin(async move {
+                Ok(req.into_response(
+                    HttpResponse::Unauthorized()
+                        .json(serde_json::json!({ "error": "API key required" }))
+                        .map_into_boxed_body(),
+                ))
+            });
+        };
+
+        let Some((client_name, scopes)) = self.registry.authenticate(&key) else {
+            log::warn!("Invalid or revoked API key from IP: {:?}", req.peer_addr());
+            return Box::pin(async move {
+                Ok(req.into_response(
+                    HttpResponse::Unauthorized()
+                        .json(serde_json::json!({ "error": "Invalid API key" }))
+                        .map_into_boxed_body(),
+                ))
+            });
+        };
+
+        if let Some(scope) = &self.required_scope {
+            if !scopes.contains(scope.as_str()) {
+                log::warn!(
+                    "API key client '{}' missing required scope '{}' for {}",
+                    client_name,
+                    scope,
+                    req.path()
+                );
+                return Box::pin(async move {
                     Ok(req.into_response(
-                        HttpResponse::Unauthorized()
-                            .json(serde_json::json!({
-                                "error": "API key required"
-                            }))
-                            .map_into_boxed_body()
+                        HttpResponse::Forbidden()
+                            .json(serde_json::json!({ "error": "Insufficient scope" }))
+                            .map_into_boxed_body(),
                     ))
-                })
+                });
             }
         }
+
+        log::info!("API key auth: authenticated client '{}' for {}", client_name, req.path());
+        req.extensions_mut().insert(ApiKeyClient(client_name));
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            Ok(res.map_into_boxed_body())
+        })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,178 @@
+use actix_web::{
+    body::{to_bytes, BoxBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    Error, HttpResponse,
+};
+use flate2::{write::DeflateEncoder, write::GzEncoder, Compression};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::io::Write;
+
+/// The content-coding negotiated from a request's `Accept-Encoding` header.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+}
+
+/// Picks gzip over deflate when a client advertises both, and ignores a
+/// coding the client has explicitly disabled with `;q=0`. Returns `None`
+/// (identity, uncompressed) if neither is acceptable.
+fn negotiate_encoding(accept_encoding: Option<&header::HeaderValue>) -> Option<Encoding> {
+    let raw = accept_encoding?.to_str().ok()?;
+
+    let accepts = |coding: &str| {
+        raw.split(',').any(|candidate| {
+            let candidate = candidate.trim();
+            let name = candidate.split(';').next().unwrap_or("").trim();
+            if !name.eq_ignore_ascii_case(coding) {
+                return false;
+            }
+            !candidate
+                .split(';')
+                .skip(1)
+                .any(|param| param.trim().eq_ignore_ascii_case("q=0"))
+        })
+    };
+
+    if accepts("gzip") {
+        Some(Encoding::Gzip)
+    } else if accepts("deflate") {
+        Some(Encoding::Deflate)
+    } else {
+        None
+    }
+}
+
+fn compress(encoding: Encoding, body: &[u8]) -> std::io::Result<Vec<u8>> {
+    match encoding {
+        Encoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()
+        }
+        Encoding::Deflate => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()
+        }
+    }
+}
+
+/// Transparently gzip/deflate-encodes response bodies at or above
+/// `min_size_bytes`, based on the request's `Accept-Encoding` header.
+///
+/// Bodies below the threshold (a health check, a `204 No Content`) are left
+/// untouched - compressing them would add overhead without meaningfully
+/// reducing bandwidth. `Vary: Accept-Encoding` is set on every response
+/// whose body is read in full, so caches don't serve a compressed response
+/// to a client that can't decode it.
+pub struct ResponseCompression {
+    min_size_bytes: usize,
+}
+
+impl ResponseCompression {
+    pub fn new(min_size_bytes: usize) -> Self {
+        Self { min_size_bytes }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ResponseCompression
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ResponseCompressionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ResponseCompressionMiddleware {
+            service,
+            min_size_bytes: self.min_size_bytes,
+        }))
+    }
+}
+
+pub struct ResponseCompressionMiddleware<S> {
+    service: S,
+    min_size_bytes: usize,
+}
+
+impl<S, B> Service<ServiceRequest> for ResponseCompressionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let encoding = negotiate_encoding(req.headers().get(header::ACCEPT_ENCODING));
+        let min_size_bytes = self.min_size_bytes;
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+            let request = res.request().clone();
+            let status = res.status();
+            let headers = res.headers().clone();
+
+            let body_bytes = match to_bytes(res.into_body()).await {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    // The body stream returned an error before it ended, so
+                    // its bytes are gone - respond with the original status
+                    // and headers and an empty body rather than fail the
+                    // request.
+                    let mut builder = HttpResponse::build(status);
+                    for (name, value) in headers.iter() {
+                        builder.insert_header((name.clone(), value.clone()));
+                    }
+                    return Ok(ServiceResponse::new(request, builder.finish()));
+                }
+            };
+
+            let mut builder = HttpResponse::build(status);
+            for (name, value) in headers.iter() {
+                if name == header::CONTENT_LENGTH || name == header::CONTENT_ENCODING {
+                    continue;
+                }
+                builder.insert_header((name.clone(), value.clone()));
+            }
+            builder.insert_header((header::VARY, "Accept-Encoding"));
+
+            let response = match encoding.filter(|_| body_bytes.len() >= min_size_bytes) {
+                Some(encoding) => match compress(encoding, &body_bytes) {
+                    Ok(compressed) => builder
+                        .insert_header((header::CONTENT_ENCODING, encoding.as_str()))
+                        .body(compressed),
+                    Err(e) => {
+                        log::warn!("Response compression failed, sending body uncompressed: {}", e);
+                        builder.body(body_bytes)
+                    }
+                },
+                None => builder.body(body_bytes),
+            };
+
+            Ok(ServiceResponse::new(request, response))
+        })
+    }
+}
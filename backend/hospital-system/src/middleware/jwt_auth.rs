@@ -0,0 +1,235 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error, HttpMessage, HttpResponse,
+    body::{BoxBody, MessageBody},
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+
+use super::auth::{ApiKeyClient, ApiKeyRegistry};
+
+/// Claims carried by a service-to-service JWT
+///
+/// Attached to request extensions by [`JwtAuth`] so handlers can read the
+/// caller's identity and scopes with `req.extensions().get::<Claims>()`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// Subject - the authenticated service identity (e.g. "hospital-system")
+    pub sub: String,
+    /// Coarse role assigned to the subject (e.g. "operator", "service")
+    pub role: String,
+    /// Space-separated scopes, e.g. "read:patients write:patients"
+    #[serde(default)]
+    pub scope: String,
+    pub exp: usize,
+    #[serde(default)]
+    pub nbf: usize,
+    pub aud: String,
+}
+
+impl Claims {
+    /// Returns true if this token carries the given scope
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope.split_whitespace().any(|s| s == scope)
+    }
+}
+
+/// JWT bearer-token authentication middleware for `/api/shared/*`
+///
+/// Decodes and validates the `Authorization: Bearer <token>` header using the
+/// configured HS256 secret, checking `exp`/`nbf`/`aud` before the request is
+/// allowed to proceed. On success, the decoded [`Claims`] are attached to the
+/// request extensions. If `required_scope` is set, requests without that
+/// scope are rejected with 403 instead of reaching the handler.
+///
+/// During the migration off the single shared `API_KEY`, requests carrying a
+/// valid `X-API-Key` header instead of a bearer token are still accepted when
+/// `legacy_api_keys` is `Some`, checked against the same per-client, scoped,
+/// revocable [`ApiKeyRegistry`] that backs [`crate::middleware::ApiKeyAuth`] -
+/// this fallback should be disabled once all consumers have moved to
+/// token-based auth.
+pub struct JwtAuth {
+    secret: Rc<String>,
+    audience: Rc<String>,
+    required_scope: Option<Rc<String>>,
+    legacy_api_keys: Option<Arc<ApiKeyRegistry>>,
+}
+
+impl JwtAuth {
+    pub fn new(secret: String, audience: String) -> Self {
+        Self {
+            secret: Rc::new(secret),
+            audience: Rc::new(audience),
+            required_scope: None,
+            legacy_api_keys: None,
+        }
+    }
+
+    /// Require a specific scope to be present in the token's `scope` claim
+    /// (for a bearer JWT) or the matched client's scope list (for a legacy
+    /// API key)
+    pub fn require_scope(mut self, scope: impl Into<String>) -> Self {
+        self.required_scope = Some(Rc::new(scope.into()));
+        self
+    }
+
+    /// Enable the legacy `X-API-Key` fallback during migration, resolved
+    /// against `registry`
+    pub fn with_legacy_api_keys(mut self, registry: Arc<ApiKeyRegistry>) -> Self {
+        self.legacy_api_keys = Some(registry);
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for JwtAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = JwtAuthMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(JwtAuthMiddleware {
+            service,
+            secret: self.secret.clone(),
+            audience: self.audience.clone(),
+            required_scope: self.required_scope.clone(),
+            legacy_api_keys: self.legacy_api_keys.clone(),
+        }))
+    }
+}
+
+pub struct JwtAuthMiddleware<S> {
+    service: S,
+    secret: Rc<String>,
+    audience: Rc<String>,
+    required_scope: Option<Rc<String>>,
+    legacy_api_keys: Option<Arc<ApiKeyRegistry>>,
+}
+
+fn unauthorized(message: &str) -> HttpResponse {
+    HttpResponse::Unauthorized().json(serde_json::json!({ "error": message }))
+}
+
+fn forbidden(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(serde_json::json!({ "error": message }))
+}
+
+impl<S, B> Service<ServiceRequest> for JwtAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let bearer = req
+            .headers()
+            .get(actix_web::http::header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .map(|s| s.to_string());
+
+        if let Some(token) = bearer {
+            let secret = self.secret.clone();
+            let audience = self.audience.clone();
+            let required_scope = self.required_scope.clone();
+
+            let mut validation = Validation::new(Algorithm::HS256);
+            validation.set_audience(&[audience.as_str()]);
+            validation.validate_nbf = true;
+
+            match decode::<Claims>(&token, &DecodingKey::from_secret(secret.as_bytes()), &validation) {
+                Ok(data) => {
+                    let claims = data.claims;
+
+                    if let Some(scope) = &required_scope {
+                        if !claims.has_scope(scope) {
+                            log::warn!(
+                                "JWT auth: subject '{}' missing required scope '{}' for {}",
+                                claims.sub, scope, req.path()
+                            );
+                            return Box::pin(async move {
+                                Ok(req.into_response(forbidden("Insufficient scope").map_into_boxed_body()))
+                            });
+                        }
+                    }
+
+                    log::info!("JWT auth: authenticated subject '{}' for {}", claims.sub, req.path());
+                    req.extensions_mut().insert(claims);
+
+                    let fut = self.service.call(req);
+                    return Box::pin(async move {
+                        let res = fut.await?;
+                        Ok(res.map_into_boxed_body())
+                    });
+                }
+                Err(e) => {
+                    log::warn!("JWT auth: token validation failed for {}: {}", req.path(), e);
+                    return Box::pin(async move {
+                        Ok(req.into_response(unauthorized("Invalid or expired token").map_into_boxed_body()))
+                    });
+                }
+            }
+        }
+
+        // No bearer token - fall back to the legacy shared API key registry while it migrates out
+        if let Some(registry) = &self.legacy_api_keys {
+            let provided = req
+                .headers()
+                .get("X-API-Key")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+
+            if let Some(key) = provided {
+                if let Some((client_name, scopes)) = registry.authenticate(&key) {
+                    let scope_ok = self
+                        .required_scope
+                        .as_ref()
+                        .map(|s| scopes.contains(s.as_str()))
+                        .unwrap_or(true);
+
+                    if scope_ok {
+                        log::warn!(
+                            "JWT auth: request to {} authenticated via legacy API key fallback (client '{}')",
+                            req.path(), client_name
+                        );
+                        req.extensions_mut().insert(ApiKeyClient(client_name));
+                        let fut = self.service.call(req);
+                        return Box::pin(async move {
+                            let res = fut.await?;
+                            Ok(res.map_into_boxed_body())
+                        });
+                    }
+
+                    log::warn!(
+                        "JWT auth: legacy API key client '{}' missing required scope for {}",
+                        client_name, req.path()
+                    );
+                    return Box::pin(async move {
+                        Ok(req.into_response(forbidden("Insufficient scope").map_into_boxed_body()))
+                    });
+                }
+            }
+        }
+
+        log::warn!("JWT auth: missing or unsupported credentials for {}", req.path());
+        Box::pin(async move {
+            Ok(req.into_response(unauthorized("Bearer token required").map_into_boxed_body()))
+        })
+    }
+}
@@ -1,8 +1,18 @@
 pub mod auth;
+pub mod jwt_auth;
+pub mod policy_auth;
+pub mod user_session;
 pub mod rate_limit;
+pub mod shared_rate_limit;
 pub mod sanitize_logs;
 pub mod csrf;
+pub mod compression;
 
-pub use auth::ApiKeyAuth;
-pub use rate_limit::{configure_rate_limiter, configure_shared_api_rate_limiter};
-pub use csrf::CsrfProtection;
\ No newline at end of file
+pub use auth::{ApiKeyAuth, ApiKeyClient, ApiKeyRegistry, spawn_reload_task as spawn_api_key_reload_task};
+pub use jwt_auth::JwtAuth;
+pub use policy_auth::{AuthConfig, GuardedData, ReadPatients, ViewFlagged, WritePatients};
+pub use user_session::{AuthenticatedUser, UserClaims};
+pub use rate_limit::configure_rate_limiter;
+pub use shared_rate_limit::{SharedApiLimits, SharedApiRateLimiter, SharedRateLimit};
+pub use csrf::CsrfProtection;
+pub use compression::ResponseCompression;
\ No newline at end of file
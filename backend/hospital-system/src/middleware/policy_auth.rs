@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::marker::PhantomData;
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest, HttpResponse};
+use futures_util::future::{ready, Ready};
+
+/// A single capability a `X-API-Key` can be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    ReadPatients,
+    ViewFlagged,
+    WritePatients,
+}
+
+impl Scope {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "read_patients" => Some(Scope::ReadPatients),
+            "view_flagged" => Some(Scope::ViewFlagged),
+            "write_patients" => Some(Scope::WritePatients),
+            _ => None,
+        }
+    }
+}
+
+/// The scopes granted to one API key.
+#[derive(Debug, Clone, Default)]
+pub struct ScopeSet(HashSet<Scope>);
+
+impl ScopeSet {
+    pub fn contains(&self, scope: Scope) -> bool {
+        self.0.contains(&scope)
+    }
+}
+
+/// A named authorization check against a caller's [`ScopeSet`].
+///
+/// Concrete policies (below) are zero-sized marker types, so `GuardedData<P>`
+/// picks the right check purely from its type parameter - a handler that
+/// needs `ViewFlagged` just takes a `GuardedData<ViewFlagged>` argument,
+/// instead of every route needing its own `Transform`/`Service` pair wired
+/// up as a separate scoped service in `main.rs`.
+pub trait Policy {
+    fn authenticate(key_scopes: &ScopeSet) -> bool;
+}
+
+pub struct ReadPatients;
+impl Policy for ReadPatients {
+    fn authenticate(key_scopes: &ScopeSet) -> bool {
+        key_scopes.contains(Scope::ReadPatients)
+    }
+}
+
+pub struct ViewFlagged;
+impl Policy for ViewFlagged {
+    fn authenticate(key_scopes: &ScopeSet) -> bool {
+        key_scopes.contains(Scope::ViewFlagged)
+    }
+}
+
+pub struct WritePatients;
+impl Policy for WritePatients {
+    fn authenticate(key_scopes: &ScopeSet) -> bool {
+        key_scopes.contains(Scope::WritePatients)
+    }
+}
+
+/// Maps each configured `X-API-Key` value to the scopes it carries.
+///
+/// Loaded once at startup from `PATIENT_API_KEYS`
+/// (`key1:read_patients+view_flagged,key2:write_patients`) and registered as
+/// `web::Data<AuthConfig>` so [`GuardedData`] can look up the caller's key
+/// without touching the environment on every request. This replaces the
+/// single all-or-nothing `API_KEY` that `ApiKeyAuth` checked - the police
+/// integration can now hold a key scoped to `view_flagged` only, while an
+/// internal service holds one scoped to `write_patients`.
+#[derive(Clone, Default)]
+pub struct AuthConfig {
+    keys: HashMap<String, ScopeSet>,
+}
+
+impl AuthConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let raw = env::var("PATIENT_API_KEYS").unwrap_or_default();
+        let mut keys = HashMap::new();
+
+        for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (key, scopes) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("malformed PATIENT_API_KEYS entry: '{}'", entry))?;
+
+            if key.is_empty() {
+                return Err(format!("malformed PATIENT_API_KEYS entry: '{}'", entry));
+            }
+
+            let scope_set = scopes
+                .split('+')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    Scope::parse(s).ok_or_else(|| format!("unknown scope '{}' in PATIENT_API_KEYS", s))
+                })
+                .collect::<Result<HashSet<_>, _>>()?;
+
+            keys.insert(key.to_string(), ScopeSet(scope_set));
+        }
+
+        Ok(AuthConfig { keys })
+    }
+
+    fn scopes_for(&self, key: &str) -> Option<&ScopeSet> {
+        self.keys.get(key)
+    }
+}
+
+/// Extractor that grants access only to callers whose `X-API-Key` carries
+/// the scope required by `P`.
+///
+/// Handlers take it as a plain argument, e.g.
+/// `get_flagged_patients(pool: web::Data<PgPool>, _scope: GuardedData<ViewFlagged>)`
+/// - extraction itself fails with `403` if the key is missing, unknown, or
+/// lacks the scope `P` requires.
+pub struct GuardedData<P: Policy>(PhantomData<P>);
+
+impl<P: Policy> FromRequest for GuardedData<P> {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let auth_config = match req.app_data::<web::Data<AuthConfig>>() {
+            Some(config) => config,
+            None => {
+                return ready(Err(actix_web::error::ErrorInternalServerError(
+                    "AuthConfig not configured",
+                )))
+            }
+        };
+
+        let provided_key = req
+            .headers()
+            .get("X-API-Key")
+            .and_then(|h| h.to_str().ok());
+
+        let authorized = provided_key
+            .and_then(|key| auth_config.scopes_for(key))
+            .is_some_and(P::authenticate);
+
+        if authorized {
+            ready(Ok(GuardedData(PhantomData)))
+        } else {
+            log::warn!(
+                "Scope check failed from IP {:?}: missing required scope",
+                req.peer_addr()
+            );
+            ready(Err(actix_web::error::InternalError::from_response(
+                "missing required scope",
+                HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": "Insufficient scope for this operation"
+                })),
+            )
+            .into()))
+        }
+    }
+}
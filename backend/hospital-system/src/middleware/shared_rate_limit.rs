@@ -0,0 +1,352 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error, HttpMessage, HttpResponse,
+    body::{BoxBody, MessageBody},
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+
+use super::auth::ApiKeyClient;
+use super::jwt_auth::Claims;
+use crate::utils::audit::{Action, AuditLog, AuditResult, EventType};
+
+/// Configurable thresholds for [`SharedApiRateLimiter`], loaded once at
+/// startup - see `Config::from_env` for where the `SHARED_API_*` env vars
+/// are parsed.
+#[derive(Debug, Clone)]
+pub struct SharedApiLimits {
+    pub requests_per_minute: u32,
+    pub bulk_requests_per_hour: u32,
+    pub anomaly_distinct_ids_threshold: u32,
+    pub anomaly_window_secs: u64,
+    pub anomaly_not_found_threshold: u32,
+}
+
+/// A classic token bucket: `capacity` tokens refilling at `refill_per_sec`,
+/// one token consumed per allowed request.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, refill_per_sec: f64) -> Self {
+        Self {
+            tokens: capacity as f64,
+            capacity: capacity as f64,
+            refill_per_sec,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Attempts to consume one token. `Ok` if allowed; `Err(retry_after)`
+    /// with the wait until a token would next be available otherwise.
+    fn try_consume(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let deficit = 1.0 - self.tokens;
+            let wait_secs = (deficit / self.refill_per_sec).ceil().max(1.0);
+            Err(Duration::from_secs_f64(wait_secs))
+        }
+    }
+}
+
+/// Per-client counters: the two token buckets enforcing hard limits, plus a
+/// short rolling history used to flag suspicious access patterns.
+struct ClientBucket {
+    minute: TokenBucket,
+    bulk_hourly: TokenBucket,
+    recent_personal_ids: VecDeque<(Instant, String)>,
+    recent_not_found: VecDeque<Instant>,
+    flagged_until: Option<Instant>,
+}
+
+impl ClientBucket {
+    fn new(limits: &SharedApiLimits) -> Self {
+        Self {
+            minute: TokenBucket::new(limits.requests_per_minute, limits.requests_per_minute as f64 / 60.0),
+            bulk_hourly: TokenBucket::new(limits.bulk_requests_per_hour, limits.bulk_requests_per_hour as f64 / 3600.0),
+            recent_personal_ids: VecDeque::new(),
+            recent_not_found: VecDeque::new(),
+            flagged_until: None,
+        }
+    }
+}
+
+/// Shared, per-client rate limiting and anomaly detection for `/api/shared`.
+///
+/// Keyed by the identity resolved by [`super::auth::ApiKeyAuth`] /
+/// [`super::jwt_auth::JwtAuth`] (the legacy-key client name, or the JWT
+/// `sub`) rather than by IP - a single partner calling from many addresses,
+/// or many partners behind one NAT, should each get their own bucket.
+/// `Clone` is cheap (the counters live behind an `Arc`), so the same
+/// instance can be held by [`SharedRateLimit`] and registered as
+/// `web::Data` for handlers in the same scope to read.
+#[derive(Clone)]
+pub struct SharedApiRateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+struct RateLimiterInner {
+    limits: SharedApiLimits,
+    buckets: Mutex<HashMap<String, ClientBucket>>,
+}
+
+/// Outcome of a rate/anomaly check for one request.
+enum Decision {
+    Allowed,
+    RateLimited(Duration),
+}
+
+impl SharedApiRateLimiter {
+    pub fn new(limits: SharedApiLimits) -> Self {
+        Self {
+            inner: Arc::new(RateLimiterInner {
+                limits,
+                buckets: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    fn check_and_consume(&self, client_key: &str, is_bulk: bool) -> Decision {
+        let mut buckets = self.inner.buckets.lock().expect("rate limiter lock poisoned");
+        let bucket = buckets
+            .entry(client_key.to_string())
+            .or_insert_with(|| ClientBucket::new(&self.inner.limits));
+
+        if let Err(retry_after) = bucket.minute.try_consume() {
+            return Decision::RateLimited(retry_after);
+        }
+
+        if is_bulk {
+            if let Err(retry_after) = bucket.bulk_hourly.try_consume() {
+                return Decision::RateLimited(retry_after);
+            }
+        }
+
+        Decision::Allowed
+    }
+
+    /// Records the outcome of a single-patient lookup and reports whether
+    /// this request pushed the client over an anomaly threshold - an
+    /// unusual burst of distinct `personal_id`s, or repeated not-found
+    /// results suggesting the caller is enumerating IDs. Thresholds reset
+    /// once flagged, so the same burst isn't re-reported on every
+    /// subsequent request.
+    fn record_lookup(&self, client_key: &str, personal_id: &str, found: bool) -> Option<&'static str> {
+        let window = Duration::from_secs(self.inner.limits.anomaly_window_secs);
+        let now = Instant::now();
+        let mut buckets = self.inner.buckets.lock().expect("rate limiter lock poisoned");
+        let bucket = buckets
+            .entry(client_key.to_string())
+            .or_insert_with(|| ClientBucket::new(&self.inner.limits));
+
+        if bucket.flagged_until.is_some_and(|until| now < until) {
+            return None;
+        }
+
+        bucket.recent_personal_ids.push_back((now, personal_id.to_string()));
+        while bucket.recent_personal_ids.front().is_some_and(|(t, _)| now.duration_since(*t) > window) {
+            bucket.recent_personal_ids.pop_front();
+        }
+
+        if !found {
+            bucket.recent_not_found.push_back(now);
+            while bucket.recent_not_found.front().is_some_and(|t| now.duration_since(*t) > window) {
+                bucket.recent_not_found.pop_front();
+            }
+        }
+
+        let distinct_ids: std::collections::HashSet<&str> = bucket
+            .recent_personal_ids
+            .iter()
+            .map(|(_, id)| id.as_str())
+            .collect();
+
+        let reason = if distinct_ids.len() as u32 >= self.inner.limits.anomaly_distinct_ids_threshold {
+            Some("burst of distinct personal_id lookups")
+        } else if bucket.recent_not_found.len() as u32 >= self.inner.limits.anomaly_not_found_threshold {
+            Some("repeated not-found results")
+        } else {
+            None
+        };
+
+        if reason.is_some() {
+            bucket.flagged_until = Some(now + window);
+        }
+
+        reason
+    }
+}
+
+/// Resolves the identity a request was authenticated as, as stashed by
+/// [`super::auth::ApiKeyAuth`] / [`super::jwt_auth::JwtAuth`] earlier in the
+/// middleware chain. `None` should not happen in practice - this middleware
+/// is always wrapped inside one of those two - but is handled defensively.
+fn resolve_client_key(req: &ServiceRequest) -> Option<String> {
+    if let Some(client) = req.extensions().get::<ApiKeyClient>() {
+        return Some(client.0.clone());
+    }
+    req.extensions().get::<Claims>().map(|claims| claims.sub.clone())
+}
+
+/// Redacts a personal ID for logging, matching the convention used in
+/// [`crate::api::shared`].
+fn sanitize_personal_id(pid: &str) -> String {
+    if pid.len() >= 9 {
+        format!("{}-****", &pid[..8])
+    } else {
+        "INVALID-****".to_string()
+    }
+}
+
+/// Per-client token-bucket rate limiting (requests/minute and bulk-list
+/// calls/hour) and anomaly flagging for `/api/shared`. Must be wrapped
+/// *inside* [`super::auth::ApiKeyAuth`] / [`super::jwt_auth::JwtAuth`] (i.e.
+/// added to the scope before them) so the caller's identity is already
+/// attached to request extensions by the time this middleware runs.
+pub struct SharedRateLimit {
+    limiter: SharedApiRateLimiter,
+}
+
+impl SharedRateLimit {
+    pub fn new(limiter: SharedApiRateLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SharedRateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SharedRateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SharedRateLimitMiddleware {
+            service,
+            limiter: self.limiter.clone(),
+        }))
+    }
+}
+
+pub struct SharedRateLimitMiddleware<S> {
+    service: S,
+    limiter: SharedApiRateLimiter,
+}
+
+impl<S, B> Service<ServiceRequest> for SharedRateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(client_key) = resolve_client_key(&req) else {
+            log::error!("SharedRateLimit: no resolved client identity for {} - is it wrapped inside the auth middleware?", req.path());
+            return Box::pin(async move {
+                Ok(req.into_response(
+                    HttpResponse::Unauthorized()
+                        .json(serde_json::json!({ "error": "Missing or invalid credentials" }))
+                        .map_into_boxed_body(),
+                ))
+            });
+        };
+
+        let path = req.path().to_string();
+        let is_bulk = path == "/api/shared/patients" || path == "/api/shared/patients/flagged";
+
+        match self.limiter.check_and_consume(&client_key, is_bulk) {
+            Decision::RateLimited(retry_after) => {
+                log::warn!(
+                    "Shared API: client '{}' rate-limited on {} (retry after {}s)",
+                    client_key, path, retry_after.as_secs()
+                );
+                let ip = req.peer_addr().map(|a| a.ip());
+                AuditLog::new(
+                    EventType::SuspiciousAccess,
+                    client_key,
+                    Action::Read,
+                    path,
+                    AuditResult::Failure,
+                )
+                .with_ip(ip)
+                .with_details("rate limit exceeded".to_string())
+                .write();
+
+                return Box::pin(async move {
+                    Ok(req.into_response(
+                        HttpResponse::TooManyRequests()
+                            .insert_header(("Retry-After", retry_after.as_secs().to_string()))
+                            .json(serde_json::json!({ "error": "Rate limit exceeded" }))
+                            .map_into_boxed_body(),
+                    ))
+                });
+            }
+            Decision::Allowed => {}
+        }
+
+        // Single-patient lookups feed the anomaly detector; bulk/list calls
+        // don't carry a single personal_id to track.
+        let personal_id = path
+            .strip_prefix("/api/shared/patients/")
+            .filter(|rest| !rest.is_empty() && *rest != "flagged")
+            .map(|s| s.to_string());
+
+        let limiter = self.limiter.clone();
+        let ip = req.peer_addr().map(|a| a.ip());
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+            let res = res.map_into_boxed_body();
+
+            if let Some(pid) = personal_id {
+                let found = res.status().is_success();
+                if let Some(reason) = limiter.record_lookup(&client_key, &pid, found) {
+                    log::warn!(
+                        "Shared API: flagged client '{}' for suspicious access ({})",
+                        client_key, reason
+                    );
+                    AuditLog::new(
+                        EventType::SuspiciousAccess,
+                        client_key,
+                        Action::Read,
+                        format!("patient:{}", sanitize_personal_id(&pid)),
+                        AuditResult::Failure,
+                    )
+                    .with_ip(ip)
+                    .with_details(reason.to_string())
+                    .write();
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
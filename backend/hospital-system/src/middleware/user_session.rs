@@ -0,0 +1,78 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, error::InternalError, web, FromRequest, HttpRequest, HttpResponse};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::config::JwtSecret;
+
+/// Claims carried by a human user session JWT, minted by `POST /auth/login`
+/// and renewed by `POST /auth/refresh`.
+///
+/// Deliberately separate from [`crate::middleware::jwt_auth::Claims`], which
+/// identifies a calling *service* (e.g. the police system) rather than a
+/// logged-in person - the two tokens are never interchangeable even though
+/// both are HS256 JWTs signed with the same `JWT_SECRET`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserClaims {
+    pub sub: String,
+    pub roles: Vec<String>,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+impl UserClaims {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Extractor that decodes and validates a user session's
+/// `Authorization: Bearer <token>` header, so patient handlers can branch on
+/// `UserClaims::roles` instead of re-parsing the token themselves.
+pub struct AuthenticatedUser(pub UserClaims);
+
+impl FromRequest for AuthenticatedUser {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let jwt_secret = match req.app_data::<web::Data<JwtSecret>>() {
+            Some(secret) => secret,
+            None => {
+                return ready(Err(unauthorized_error(
+                    "JwtSecret not configured",
+                )))
+            }
+        };
+
+        let token = req
+            .headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "));
+
+        let token = match token {
+            Some(token) => token,
+            None => return ready(Err(unauthorized_error("Missing bearer token"))),
+        };
+
+        let validation = Validation::new(Algorithm::HS256);
+
+        match decode::<UserClaims>(token, &DecodingKey::from_secret(jwt_secret.0.as_bytes()), &validation) {
+            Ok(data) => ready(Ok(AuthenticatedUser(data.claims))),
+            Err(e) => {
+                log::warn!("Rejected user session token: {}", e);
+                ready(Err(unauthorized_error("Invalid or expired session")))
+            }
+        }
+    }
+}
+
+fn unauthorized_error(message: &str) -> actix_web::Error {
+    InternalError::from_response(
+        message.to_string(),
+        HttpResponse::Unauthorized().json(serde_json::json!({ "error": message })),
+    )
+    .into()
+}
@@ -0,0 +1,21 @@
+use serde::Serialize;
+use sqlx::FromRow;
+use chrono::NaiveDateTime;
+
+/// Metadata row for a clinical document/image uploaded against a patient.
+///
+/// `storage_path` is deliberately not serialized - it's the on-disk (or
+/// object-store) key used to stream the file back in `GET
+/// /attachments/{id}`, not something a client should see or rely on.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Attachment {
+    pub id: i32,
+    pub patient_id: i32,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    #[serde(skip_serializing)]
+    pub storage_path: String,
+    pub created_at: NaiveDateTime,
+}
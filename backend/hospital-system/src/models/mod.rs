@@ -0,0 +1,11 @@
+pub mod patient;
+pub mod record;
+pub mod user;
+pub mod attachment;
+pub mod shared_access;
+
+pub use patient::{Patient, CreatePatient, UpdatePatient};
+pub use record::MedicalRecord;
+pub use user::User;
+pub use attachment::Attachment;
+pub use shared_access::{AccessStatus, AccessType, CreateAccessRequest, SharedAccessRequest};
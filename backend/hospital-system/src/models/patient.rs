@@ -1,12 +1,25 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use chrono::NaiveDateTime;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Patient {
     pub id: i32,
-    pub patient_id: String,
-    pub name: String,
+    pub full_name: String,
+    pub personal_id: String,
+    pub flag: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreatePatient {
+    pub full_name: String,
+    pub personal_id: String,
+    pub flag: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdatePatient {
+    pub full_name: Option<String>,
     pub personal_id: Option<String>,
-    pub created_at: NaiveDateTime,
-}
\ No newline at end of file
+    pub flag: Option<bool>,
+}
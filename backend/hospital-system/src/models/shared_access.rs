@@ -0,0 +1,88 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Number of days a break-glass request waits for an explicit denial before
+/// it auto-approves, when the caller doesn't specify one.
+pub const DEFAULT_WAIT_TIME_DAYS: i32 = 3;
+
+/// Floor on a caller-supplied `wait_time_days`. Without this, a caller could
+/// request `wait_time_days: 0` (or negative) and have `auto_approve_lapsed`
+/// grant the request on its very next hourly sweep - defeating the point of
+/// the break-glass delay, which exists to give a human a window to deny it.
+pub const MIN_WAIT_TIME_DAYS: i32 = 1;
+
+/// How much a grant lets the holder see. Both values gate the same shared
+/// read endpoints today; `Full` is reserved for when the shared API grows
+/// endpoints beyond read-only patient lookups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum AccessType {
+    View,
+    Full,
+}
+
+impl AccessType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessType::View => "View",
+            AccessType::Full => "Full",
+        }
+    }
+}
+
+/// Lifecycle of a [`SharedAccessRequest`]: `Requested` auto-advances to
+/// `Approved` once `wait_time_days` elapses with no `Denied` decision, and
+/// an `Approved` grant eventually moves to `Expired`. See
+/// [`crate::shared_access::spawn_access_sweep_task`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessStatus {
+    Requested,
+    Approved,
+    Denied,
+    Expired,
+}
+
+impl AccessStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessStatus::Requested => "Requested",
+            AccessStatus::Approved => "Approved",
+            AccessStatus::Denied => "Denied",
+            AccessStatus::Expired => "Expired",
+        }
+    }
+}
+
+/// A break-glass access grant/request row for the shared API.
+///
+/// `personal_id = None` means the request covers every patient ("all")
+/// instead of a single `personal_id`. `access_type` and `status` are kept as
+/// plain strings at the row level - this repo doesn't use native Postgres
+/// enums - with [`AccessType`]/[`AccessStatus`] as the typed vocabulary
+/// callers construct and compare them against.
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct SharedAccessRequest {
+    pub id: i32,
+    pub requester: String,
+    pub personal_id: Option<String>,
+    pub access_type: String,
+    pub status: String,
+    pub wait_time_days: i32,
+    pub recovery_initiated_at: NaiveDateTime,
+    pub decided_at: Option<NaiveDateTime>,
+    pub decided_by: Option<String>,
+    pub last_notification_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Request body for `POST /api/shared/access-requests`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAccessRequest {
+    /// Swedish personal ID to request access to, or omitted for "all".
+    pub personal_id: Option<String>,
+    pub access_type: AccessType,
+    /// Days to wait for a denial before auto-approving. Defaults to
+    /// [`DEFAULT_WAIT_TIME_DAYS`] when omitted.
+    pub wait_time_days: Option<i32>,
+}
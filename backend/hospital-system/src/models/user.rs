@@ -0,0 +1,19 @@
+use serde::Serialize;
+use sqlx::FromRow;
+use chrono::NaiveDateTime;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct User {
+    pub id: i32,
+    pub username: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub roles: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl User {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
@@ -0,0 +1,85 @@
+use actix_web::{web, HttpResponse};
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::api::patients::{
+    create_patient, delete_patient, get_all_patients, get_flagged_patients, get_patient_by_id,
+    get_patient_by_personal_id, update_patient,
+};
+use crate::api::shared::{
+    create_access_request, get_all_shared_patients, get_shared_flagged_patients,
+    get_shared_patient_info, PatientPage,
+};
+use crate::api::shared_access::{approve_access_request, deny_access_request};
+use crate::models::{
+    AccessType, CreateAccessRequest, CreatePatient, Patient, SharedAccessRequest, UpdatePatient,
+};
+
+/// Registers the `X-API-Key` security scheme referenced by every
+/// `#[utoipa::path(security(("api_key" = [])))]` annotation - without this,
+/// utoipa would emit paths that claim to require auth but never describe
+/// how to provide it.
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi
+            .components
+            .get_or_insert_with(utoipa::openapi::Components::new);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+    }
+}
+
+/// The hospital system's OpenAPI document: the `/patients` CRUD routes, the
+/// `/api/shared` patient and access-request routes, and the
+/// `/shared-access` approve/deny routes, with their request/response
+/// schemas and the `X-API-Key` scheme. The attachment routes and
+/// `/api/shared/sync/flag` are not described. Served as JSON at `GET /api-docs/openapi.json`
+/// so integrators (including the police-flagging client) can generate a
+/// typed client instead of hand-rolling one against the route table logged
+/// at startup.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_patients,
+        get_patient_by_id,
+        get_patient_by_personal_id,
+        create_patient,
+        update_patient,
+        delete_patient,
+        get_flagged_patients,
+        get_all_shared_patients,
+        get_shared_flagged_patients,
+        get_shared_patient_info,
+        create_access_request,
+        approve_access_request,
+        deny_access_request,
+    ),
+    components(schemas(
+        Patient, CreatePatient, UpdatePatient, PatientPage,
+        SharedAccessRequest, CreateAccessRequest, AccessType,
+    )),
+    modifiers(&ApiKeySecurity),
+    tags(
+        (name = "patients", description = "Internal patient management"),
+        (name = "shared", description = "Inter-system API consumed by police-system"),
+    )
+)]
+pub struct ApiDoc;
+
+/// GET /api-docs/openapi.json - Serves the generated OpenAPI document.
+async fn serve_openapi_spec() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+/// Configure the OpenAPI spec route. Unauthenticated - the spec describes
+/// the API's shape, not its data, and integrators need it before they hold
+/// any credentials.
+pub fn configure_openapi(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api-docs/openapi.json", web::get().to(serve_openapi_spec));
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use crate::database;
+use crate::utils::audit::{Action, AuditLog, AuditResult, EventType};
+
+/// How often the sweep checks for lapsed/stale access requests. Requests are
+/// granted on the scale of days, so checking hourly is frequent enough
+/// without hammering the database.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How long an `Approved` grant stays valid after it was decided before the
+/// sweep expires it.
+const ACCESS_GRANT_TTL_DAYS: i32 = 30;
+
+/// Spawns the background sweep that keeps break-glass access requests
+/// moving through their lifecycle without a human in the loop: a
+/// `Requested` row whose wait period has elapsed with no denial auto-
+/// approves, and an `Approved` grant past [`ACCESS_GRANT_TTL_DAYS`] expires.
+///
+/// Mirrors [`crate::tls::CertManager::spawn_renewal_task`]'s
+/// sleep-then-check loop - there's no external event to react to, just a
+/// clock condition to poll.
+pub fn spawn_access_sweep_task(pool: PgPool) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(SWEEP_INTERVAL).await;
+
+            match database::auto_approve_lapsed(&pool).await {
+                Ok(approved) => {
+                    for request in &approved {
+                        log::info!(
+                            "Shared access request {} auto-approved after {} day(s) with no denial",
+                            request.id,
+                            request.wait_time_days
+                        );
+                        AuditLog::new(
+                            EventType::AccessAutoApproved,
+                            "system:auto-approve".to_string(),
+                            Action::Update,
+                            format!("shared_access:{}", request.id),
+                            AuditResult::Success,
+                        )
+                        .write();
+                    }
+                }
+                Err(e) => log::error!("Failed to auto-approve lapsed access requests: {}", e),
+            }
+
+            match database::expire_stale_approved(&pool, ACCESS_GRANT_TTL_DAYS).await {
+                Ok(expired) => {
+                    for request in &expired {
+                        log::info!(
+                            "Shared access grant {} expired after {} day(s)",
+                            request.id,
+                            ACCESS_GRANT_TTL_DAYS
+                        );
+                        AuditLog::new(
+                            EventType::AccessExpired,
+                            "system:auto-expire".to_string(),
+                            Action::Update,
+                            format!("shared_access:{}", request.id),
+                            AuditResult::Success,
+                        )
+                        .write();
+                    }
+                }
+                Err(e) => log::error!("Failed to expire stale access grants: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,41 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use chrono::{DateTime, Utc};
+use subtle::ConstantTimeEq;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How far a signed payload's timestamp may drift from "now" before it's
+/// rejected as a possible replay.
+pub const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// Recomputes `HMAC-SHA256(shared_secret, canonical_json || sequence || timestamp)`
+/// and compares it to the signature the police system's sync worker sent,
+/// rejecting on mismatch or on a timestamp outside the ±5 minute window.
+pub fn verify(
+    shared_secret: &str,
+    canonical_json: &str,
+    sequence: i64,
+    timestamp: DateTime<Utc>,
+    signature_hex: &str,
+) -> bool {
+    let skew = (Utc::now() - timestamp).num_seconds().abs();
+    if skew > MAX_CLOCK_SKEW_SECONDS {
+        log::warn!("Sync signature rejected: timestamp skew of {}s exceeds the allowed window", skew);
+        return false;
+    }
+
+    let mut mac = match HmacSha256::new_from_slice(shared_secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(canonical_json.as_bytes());
+    mac.update(sequence.to_string().as_bytes());
+    mac.update(timestamp.timestamp().to_string().as_bytes());
+    let expected = mac.finalize().into_bytes();
+
+    match hex::decode(signature_hex) {
+        Ok(provided) => expected.as_slice().ct_eq(&provided).into(),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,267 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use instant_acme::{Account, AccountCredentials, ChallengeType, NewAccount, NewOrder, OrderStatus};
+use rustls::sign::CertifiedKey;
+use rcgen::{CertificateParams, DistinguishedName};
+
+use super::resolver::SwappableCertResolver;
+
+/// Check for renewal once a day
+const RENEW_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Renew once fewer than this many days remain on the certificate
+const RENEW_BEFORE_DAYS: i64 = 30;
+
+/// Obtains and renews certificates through ACME (Let's Encrypt by default)
+/// and installs them into a [`SwappableCertResolver`] without downtime.
+///
+/// The ACME account key and order state are persisted under `state_dir` so
+/// restarts don't re-register the account or re-issue a certificate that's
+/// still valid.
+pub struct CertManager {
+    directory_url: String,
+    domain: String,
+    contact_email: String,
+    challenge_type: ChallengeType,
+    state_dir: PathBuf,
+    resolver: SwappableCertResolver,
+}
+
+impl CertManager {
+    /// Bootstraps the manager: loads (or registers) the ACME account, and
+    /// obtains an initial certificate if none is cached or the cached one is
+    /// close to expiry. Returns the manager plus a resolver ready to hand to
+    /// `rustls::ServerConfig`.
+    pub async fn bootstrap(
+        directory_url: String,
+        domain: String,
+        contact_email: String,
+        state_dir: PathBuf,
+    ) -> Result<(Self, SwappableCertResolver), AcmeError> {
+        std::fs::create_dir_all(&state_dir).map_err(AcmeError::Io)?;
+
+        let initial = Self::load_or_issue(
+            &directory_url,
+            &domain,
+            &contact_email,
+            &state_dir,
+        )
+        .await?;
+
+        let resolver = SwappableCertResolver::new(initial);
+
+        let manager = Self {
+            directory_url,
+            domain,
+            contact_email,
+            challenge_type: ChallengeType::Http01,
+            state_dir,
+            resolver: resolver.clone(),
+        };
+
+        Ok((manager, resolver))
+    }
+
+    /// Spawns the background renewal loop. Intended to be called once at
+    /// startup and left running for the lifetime of the process.
+    pub fn spawn_renewal_task(self) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(RENEW_CHECK_INTERVAL).await;
+
+                match cert_expires_within(&self.state_dir, RENEW_BEFORE_DAYS) {
+                    Ok(true) => {
+                        log::info!("🔐 ACME certificate for {} is within {} days of expiry - renewing", self.domain, RENEW_BEFORE_DAYS);
+                        match Self::load_or_issue(&self.directory_url, &self.domain, &self.contact_email, &self.state_dir).await {
+                            Ok(renewed) => self.resolver.replace(renewed),
+                            Err(e) => log::error!("❌ ACME renewal failed for {}: {}", self.domain, e),
+                        }
+                    }
+                    Ok(false) => {
+                        log::debug!("ACME certificate for {} does not need renewal yet", self.domain);
+                    }
+                    Err(e) => log::error!("❌ Failed to inspect cached ACME certificate: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Loads the cached cert/key pair from `state_dir` if present and still
+    /// valid for more than `RENEW_BEFORE_DAYS`, otherwise drives a full ACME
+    /// order (registering the account first if no credentials are cached).
+    async fn load_or_issue(
+        directory_url: &str,
+        domain: &str,
+        contact_email: &str,
+        state_dir: &PathBuf,
+    ) -> Result<CertifiedKey, AcmeError> {
+        if let Ok(false) | Err(_) = cert_expires_within(state_dir, RENEW_BEFORE_DAYS) {
+            if let Ok(certified_key) = load_cached_certified_key(state_dir) {
+                return Ok(certified_key);
+            }
+        }
+
+        let account = Self::load_or_register_account(directory_url, contact_email, state_dir).await?;
+
+        let mut params = CertificateParams::new(vec![domain.to_string()]);
+        params.distinguished_name = DistinguishedName::new();
+
+        let mut order = account
+            .new_order(&NewOrder {
+                identifiers: &[instant_acme::Identifier::Dns(domain.to_string())],
+            })
+            .await
+            .map_err(|e| AcmeError::Acme(e.to_string()))?;
+
+        // Drive the HTTP-01 challenge for every authorization until the
+        // order is ready, then finalize and download the issued chain. An
+        // authorization that offers no HTTP-01 challenge fails the order;
+        // DNS-01 challenges are not driven.
+        let authorizations = order.authorizations().await.map_err(|e| AcmeError::Acme(e.to_string()))?;
+        for authz in &authorizations {
+            let challenge = authz
+                .challenges
+                .iter()
+                .find(|c| c.r#type == ChallengeType::Http01)
+                .ok_or_else(|| AcmeError::Acme("no HTTP-01 challenge offered".to_string()))?;
+
+            // The actual key-authorization file must be served at
+            // /.well-known/acme-challenge/<token> by the running server -
+            // wired up separately as a route that reads from `state_dir`.
+            persist_pending_challenge(state_dir, &challenge.token, &order.key_authorization(challenge).as_str())
+                .map_err(AcmeError::Io)?;
+
+            order
+                .set_challenge_ready(&challenge.url)
+                .await
+                .map_err(|e| AcmeError::Acme(e.to_string()))?;
+        }
+
+        wait_for_order_ready(&mut order).await?;
+
+        let cert_chain_pem = order
+            .finalize()
+            .await
+            .map_err(|e| AcmeError::Acme(e.to_string()))?;
+
+        persist_issued_certificate(state_dir, &cert_chain_pem).map_err(AcmeError::Io)?;
+
+        load_cached_certified_key(state_dir)
+    }
+
+    async fn load_or_register_account(
+        directory_url: &str,
+        contact_email: &str,
+        state_dir: &PathBuf,
+    ) -> Result<Account, AcmeError> {
+        let credentials_path = state_dir.join("account_credentials.json");
+
+        if let Ok(raw) = std::fs::read_to_string(&credentials_path) {
+            if let Ok(credentials) = serde_json::from_str::<AccountCredentials>(&raw) {
+                if let Ok(account) = Account::from_credentials(credentials).await {
+                    return Ok(account);
+                }
+            }
+        }
+
+        log::info!("No cached ACME account found - registering a new one with {}", directory_url);
+
+        let (account, credentials) = Account::create(
+            &NewAccount {
+                contact: &[&format!("mailto:{}", contact_email)],
+                terms_of_service_agreed: true,
+                only_return_existing: false,
+            },
+            directory_url,
+            None,
+        )
+        .await
+        .map_err(|e| AcmeError::Acme(e.to_string()))?;
+
+        let serialized = serde_json::to_string_pretty(&credentials).map_err(AcmeError::Serde)?;
+        std::fs::write(&credentials_path, serialized).map_err(AcmeError::Io)?;
+
+        Ok(account)
+    }
+}
+
+async fn wait_for_order_ready(order: &mut instant_acme::Order) -> Result<(), AcmeError> {
+    for _ in 0..10 {
+        let state = order.refresh().await.map_err(|e| AcmeError::Acme(e.to_string()))?;
+        if state.status == OrderStatus::Ready || state.status == OrderStatus::Valid {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+    Err(AcmeError::Acme("order did not become ready in time".to_string()))
+}
+
+fn persist_pending_challenge(state_dir: &PathBuf, token: &str, key_authorization: &str) -> std::io::Result<()> {
+    let challenge_dir = state_dir.join("http-01");
+    std::fs::create_dir_all(&challenge_dir)?;
+    std::fs::write(challenge_dir.join(token), key_authorization)
+}
+
+fn persist_issued_certificate(state_dir: &PathBuf, cert_chain_pem: &str) -> std::io::Result<()> {
+    std::fs::write(state_dir.join("fullchain.pem"), cert_chain_pem)
+}
+
+fn load_cached_certified_key(state_dir: &PathBuf) -> Result<CertifiedKey, AcmeError> {
+    let cert_path = state_dir.join("fullchain.pem");
+    let key_path = state_dir.join("privkey.pem");
+
+    let cert_pem = std::fs::read(&cert_path).map_err(AcmeError::Io)?;
+    let key_pem = std::fs::read(&key_path).map_err(AcmeError::Io)?;
+
+    let certs = rustls_pemfile::certs(&mut cert_pem.as_slice())
+        .map_err(|e| AcmeError::Acme(format!("invalid cached certificate: {}", e)))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect::<Vec<_>>();
+
+    let key = rustls_pemfile::pkcs8_private_keys(&mut key_pem.as_slice())
+        .map_err(|e| AcmeError::Acme(format!("invalid cached private key: {}", e)))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AcmeError::Acme("no private key found in cached key file".to_string()))?;
+
+    let signing_key = rustls::sign::any_supported_type(&rustls::PrivateKey(key))
+        .map_err(|e| AcmeError::Acme(format!("unsupported private key: {}", e)))?;
+
+    Ok(CertifiedKey::new(certs, signing_key))
+}
+
+/// Returns true if the cached certificate expires within `days`, or an error
+/// if no cached certificate exists yet (treated by callers as "must issue").
+fn cert_expires_within(state_dir: &PathBuf, days: i64) -> Result<bool, AcmeError> {
+    let cert_path = state_dir.join("fullchain.pem");
+    let pem = std::fs::read_to_string(&cert_path).map_err(AcmeError::Io)?;
+
+    let (_, parsed) = x509_parser::pem::parse_x509_pem(pem.as_bytes())
+        .map_err(|e| AcmeError::Acme(format!("failed to parse cached certificate: {}", e)))?;
+    let cert = parsed.parse_x509().map_err(|e| AcmeError::Acme(e.to_string()))?;
+
+    let not_after = cert.validity().not_after.timestamp();
+    let now = chrono::Utc::now().timestamp();
+    let days_remaining = (not_after - now) / (24 * 60 * 60);
+
+    Ok(days_remaining < days)
+}
+
+#[derive(Debug)]
+pub enum AcmeError {
+    Io(std::io::Error),
+    Serde(serde_json::Error),
+    Acme(String),
+}
+
+impl std::fmt::Display for AcmeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AcmeError::Io(e) => write!(f, "I/O error: {}", e),
+            AcmeError::Serde(e) => write!(f, "serialization error: {}", e),
+            AcmeError::Acme(msg) => write!(f, "ACME error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AcmeError {}
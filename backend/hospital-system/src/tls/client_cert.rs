@@ -0,0 +1,30 @@
+use rustls::Certificate;
+use sha2::{Digest, Sha256};
+
+/// Identity of a verified mTLS client certificate.
+///
+/// Attached to the connection data (read with `HttpRequest::conn_data`) by
+/// the `on_connect` hook in `main.rs`
+/// when the connection presented a certificate signed by `TLS_CLIENT_CA_PATH`,
+/// so shared-API handlers can log and authorize based on which trusted
+/// partner (e.g. the police system) presented the connection.
+#[derive(Debug, Clone)]
+pub struct ClientCertInfo {
+    pub subject: String,
+    pub fingerprint: String,
+}
+
+impl ClientCertInfo {
+    /// Parses the subject and computes the SHA-256 fingerprint of the leaf
+    /// certificate presented by an authenticated TLS client. Returns `None`
+    /// if the certificate can't be parsed as X.509 - the connection is still
+    /// trusted by rustls at this point, so a parse failure only costs us the
+    /// ability to log/authorize on the identity, not the handshake itself.
+    pub fn from_leaf_certificate(cert: &Certificate) -> Option<Self> {
+        let (_, parsed) = x509_parser::parse_x509_certificate(cert.0.as_slice()).ok()?;
+        let subject = parsed.subject().to_string();
+        let fingerprint = hex::encode(Sha256::digest(&cert.0));
+
+        Some(ClientCertInfo { subject, fingerprint })
+    }
+}
@@ -0,0 +1,7 @@
+pub mod cert_manager;
+pub mod client_cert;
+pub mod resolver;
+
+pub use cert_manager::CertManager;
+pub use client_cert::ClientCertInfo;
+pub use resolver::SwappableCertResolver;
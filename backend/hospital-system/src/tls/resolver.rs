@@ -0,0 +1,34 @@
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+
+/// A [`ResolvesServerCert`] whose inner certificate can be swapped out while
+/// the server is running, so certificate renewal doesn't require a restart.
+///
+/// Existing connections keep using whatever `CertifiedKey` they negotiated
+/// with; only new handshakes observe the swap.
+#[derive(Clone)]
+pub struct SwappableCertResolver {
+    current: Arc<ArcSwap<CertifiedKey>>,
+}
+
+impl SwappableCertResolver {
+    pub fn new(initial: CertifiedKey) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(initial)),
+        }
+    }
+
+    /// Atomically replace the certificate served to new handshakes
+    pub fn replace(&self, new_cert: CertifiedKey) {
+        self.current.store(Arc::new(new_cert));
+        log::info!("🔐 TLS certificate swapped in - new handshakes will use the renewed certificate");
+    }
+}
+
+impl ResolvesServerCert for SwappableCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.load_full())
+    }
+}
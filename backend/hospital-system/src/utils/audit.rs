@@ -0,0 +1,132 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Audit event types for different operations
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventType {
+    SharedApiAccess,
+    FlaggedPatientAccess,
+    AccessRequested,
+    AccessApproved,
+    AccessDenied,
+    AccessAutoApproved,
+    AccessExpired,
+    BreakGlassRead,
+    SuspiciousAccess,
+}
+
+/// Audit action types
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Action {
+    Read,
+    Create,
+    Update,
+    Delete,
+}
+
+/// Audit result status
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditResult {
+    Success,
+    Failure,
+}
+
+/// Structured audit log entry
+#[derive(Debug, Serialize)]
+pub struct AuditLog {
+    timestamp: DateTime<Utc>,
+    event_type: EventType,
+    actor: String,
+    action: Action,
+    resource: String,
+    result: AuditResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+}
+
+impl AuditLog {
+    /// Create a new audit log entry
+    pub fn new(
+        event_type: EventType,
+        actor: String,
+        action: Action,
+        resource: String,
+        result: AuditResult,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event_type,
+            actor,
+            action,
+            resource,
+            result,
+            ip_address: None,
+            details: None,
+        }
+    }
+
+    /// Add IP address to audit log
+    pub fn with_ip(mut self, ip: Option<IpAddr>) -> Self {
+        self.ip_address = ip.map(|addr| addr.to_string());
+        self
+    }
+
+    /// Add additional details to audit log
+    pub fn with_details(mut self, details: String) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Write audit log entry to the audit log target
+    pub fn write(self) {
+        match serde_json::to_string(&self) {
+            Ok(json) => log::info!(target: "audit", "{}", json),
+            Err(e) => log::error!("Failed to serialize audit log: {}", e),
+        }
+    }
+}
+
+/// Extract an actor identifier from an inbound shared-API request.
+///
+/// Prefers the `client_name` [`ApiKeyClient`] stashed in request extensions
+/// by [`crate::middleware::ApiKeyAuth`] or the legacy-key fallback in
+/// [`crate::middleware::jwt_auth::JwtAuth`] - a named partner identity is a
+/// far more useful audit actor than a hash. Falls back to hashing
+/// `X-API-Key`/bearer token material (for requests authenticated only by
+/// bearer JWT, which doesn't resolve a named client here) rather than
+/// logging it in the clear.
+pub fn extract_actor_from_request(req: &actix_web::HttpRequest) -> String {
+    use actix_web::HttpMessage;
+    use crate::middleware::ApiKeyClient;
+
+    if let Some(client) = req.extensions().get::<ApiKeyClient>() {
+        return client.0.clone();
+    }
+
+    let credential = req
+        .headers()
+        .get("X-API-Key")
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| {
+            req.headers()
+                .get("Authorization")
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.strip_prefix("Bearer "))
+        });
+
+    credential
+        .map(|key| {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(key.as_bytes());
+            format!("api_key:{:x}", hasher.finalize())[..24].to_string()
+        })
+        .unwrap_or_else(|| "internal".to_string())
+}
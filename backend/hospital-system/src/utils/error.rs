@@ -0,0 +1,133 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Crate-wide error type returned by handlers instead of hand-rolled
+/// `eprintln!` + `HttpResponse::InternalServerError()` pairs.
+///
+/// Implements [`ResponseError`] so handlers can simply `?` on a
+/// `Result<T, Error>` and let actix turn the error into a consistent JSON
+/// envelope: `{ "error": { "code", "message" }, "request_id" }`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error")]
+    Sqlx(sqlx::Error),
+
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("authentication required")]
+    Unauthorized,
+
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("validation failed: {0}")]
+    Validation(String),
+
+    #[error("conflict: {0}")]
+    Conflict(String),
+
+    #[error("service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorDetail,
+    request_id: String,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    code: &'static str,
+    message: String,
+}
+
+impl Error {
+    fn code(&self) -> &'static str {
+        match self {
+            Error::Sqlx(_) => "internal_error",
+            Error::NotFound(_) => "not_found",
+            Error::Unauthorized => "unauthorized",
+            Error::Forbidden(_) => "forbidden",
+            Error::Validation(_) => "validation_error",
+            Error::Conflict(_) => "conflict",
+            Error::ServiceUnavailable(_) => "service_unavailable",
+            Error::PayloadTooLarge(_) => "payload_too_large",
+            Error::UnsupportedMediaType(_) => "unsupported_media_type",
+        }
+    }
+
+    /// The message included in the client-facing envelope. Database errors
+    /// are deliberately generic - details go to the server log only.
+    fn client_message(&self) -> String {
+        match self {
+            Error::Sqlx(_) => "Service temporarily unavailable".to_string(),
+            Error::NotFound(resource) => format!("{} not found", resource),
+            Error::Unauthorized => "Authentication required".to_string(),
+            Error::Forbidden(msg) => msg.clone(),
+            Error::Validation(msg) => msg.clone(),
+            Error::Conflict(msg) => msg.clone(),
+            Error::ServiceUnavailable(_) => "Service temporarily unavailable".to_string(),
+            Error::PayloadTooLarge(msg) => msg.clone(),
+            Error::UnsupportedMediaType(msg) => msg.clone(),
+        }
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::Sqlx(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::Forbidden(_) => StatusCode::FORBIDDEN,
+            Error::Validation(_) => StatusCode::BAD_REQUEST,
+            Error::Conflict(_) => StatusCode::CONFLICT,
+            Error::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Error::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let request_id = Uuid::new_v4().to_string();
+
+        match self {
+            Error::Sqlx(e) => log::error!("Database error [{}]: {}", request_id, e),
+            Error::ServiceUnavailable(detail) => log::error!("Service unavailable [{}]: {}", request_id, detail),
+            _ => log::warn!("{} [{}]: {}", self.code(), request_id, self),
+        }
+
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            error: ErrorDetail {
+                code: self.code(),
+                message: self.client_message(),
+            },
+            request_id,
+        })
+    }
+}
+
+/// Maps sqlx errors onto the right client-facing variant: a missing row
+/// becomes 404, a unique-constraint violation on `personal_id` becomes a
+/// 409 conflict instead of the generic 500 `create_patient` used to return
+/// on duplicate personal IDs.
+impl From<sqlx::Error> for Error {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::RowNotFound => Error::NotFound("resource".to_string()),
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                Error::Conflict("a record with that personal ID already exists".to_string())
+            }
+            _ => Error::Sqlx(err),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod audit;
+pub mod error;
+pub mod pagination;
+pub mod validation;
+
+pub use error::Error;
+pub use pagination::PageParams;
+pub use validation::ValidatedJson;
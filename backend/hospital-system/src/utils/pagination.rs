@@ -0,0 +1,30 @@
+use serde::Deserialize;
+
+/// Default page size when a caller omits `?limit=`.
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+/// Hard ceiling on `?limit=` so a caller can't pull an entire table in one
+/// request.
+pub const MAX_PAGE_LIMIT: i64 = 500;
+
+/// Keyset-pagination query params accepted by the shared list endpoints:
+/// `?limit=N&after=<cursor>`. Results are always ordered by `id` ascending,
+/// so `after` is the `id` of the last row the caller has already seen.
+#[derive(Debug, Deserialize)]
+pub struct PageParams {
+    pub limit: Option<i64>,
+    pub after: Option<i32>,
+}
+
+impl PageParams {
+    /// The effective page size: the caller's `limit`, clamped to
+    /// `(0, MAX_PAGE_LIMIT]` and defaulting to `DEFAULT_PAGE_LIMIT`.
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    /// The keyset cursor to resume after, or `0` (before the first row,
+    /// since ids start at 1) when the caller didn't send one.
+    pub fn after_id(&self) -> i32 {
+        self.after.unwrap_or(0)
+    }
+}
@@ -0,0 +1,58 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::utils::Error;
+
+/// A drop-in replacement for `web::Json<T>` that also runs `T::validate()`
+/// before the handler ever sees the payload, rejecting with a field-level
+/// [`Error::Validation`] instead of letting a malformed body reach the
+/// database layer.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> ValidatedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> FromRequest for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json_fut = web::Json::<T>::from_request(req, payload);
+
+        Box::pin(async move {
+            let value = json_fut.await?.into_inner();
+
+            if let Err(errors) = value.validate() {
+                let detail = errors
+                    .field_errors()
+                    .iter()
+                    .map(|(field, field_errors)| {
+                        let messages: Vec<String> = field_errors
+                            .iter()
+                            .map(|e| {
+                                e.message
+                                    .clone()
+                                    .map(|m| m.to_string())
+                                    .unwrap_or_else(|| e.code.to_string())
+                            })
+                            .collect();
+                        format!("{}: {}", field, messages.join(", "))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ");
+
+                return Err(Error::Validation(detail).into());
+            }
+
+            Ok(ValidatedJson(value))
+        })
+    }
+}
@@ -0,0 +1,31 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+
+use crate::middleware::session::AuthenticatedOperator;
+use crate::utils::audit::verify_audit_chain;
+use crate::utils::ApiError;
+
+/// GET /api/shared/audit/verify - Walk the hash-chained `audit_log` table end to end
+/// and report whether it's still intact.
+///
+/// Gated by [`AuthenticatedOperator`] rather than the shared-API Signature/JWT
+/// guards - this exposes whether the audit trail itself has been tampered
+/// with, so it's restricted to the same operator session as `/suspects` and
+/// `/cases` instead of the inter-system partners the chain is recording.
+/// `main.rs` registers it ahead of the wrapped `/api/shared` scope, so those
+/// guards never see this route.
+async fn verify(
+    pool: web::Data<PgPool>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
+    let verification = verify_audit_chain(&pool).await?;
+    Ok(HttpResponse::Ok().json(verification))
+}
+
+/// Configure audit log routes
+///
+/// Routes:
+/// - GET /api/shared/audit/verify - Verify the audit log's hash chain is unbroken
+pub fn configure_audit(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/api/shared/audit").route("/verify", web::get().to(verify)));
+}
@@ -0,0 +1,183 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::middleware::session::{
+    decode_refresh_token, issue_session_tokens, logout_cookies, session_cookies,
+    OperatorRegistry, SessionSecret, TlsEnabled,
+};
+use crate::utils::ApiError;
+use crate::utils::audit::{AuditChain, AuditLog, Action, AuditResult, EventType};
+
+/// Request body for `POST /auth/login`.
+#[derive(Deserialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+/// POST /auth/login - Authenticate an operator and start a session
+///
+/// On success, sets the `token` (short-lived access) and `refresh_token`
+/// (longer-lived) HttpOnly cookies used by [`crate::middleware::session::AuthenticatedOperator`]
+/// to authorize `/suspects` and `/cases` requests.
+async fn login(
+    credentials: web::Json<LoginRequest>,
+    registry: web::Data<OperatorRegistry>,
+    secret: web::Data<SessionSecret>,
+    pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
+    req: actix_web::HttpRequest,
+    enable_tls: web::Data<TlsEnabled>,
+) -> Result<HttpResponse, ApiError> {
+    let LoginRequest { username, password } = credentials.into_inner();
+    let ip = req.peer_addr().map(|a| a.ip());
+
+    let role = match registry.authenticate(&username, &password) {
+        Some(role) => role,
+        None => {
+            AuditLog::new(
+                EventType::Authentication,
+                username.clone(),
+                Action::Read,
+                "session:login".to_string(),
+                AuditResult::Failure,
+            )
+            .with_ip(ip)
+            .with_details("Invalid credentials".to_string())
+            .write(&chain, &pool).await;
+
+            return Err(ApiError::Unauthorized {
+                code: "INVALID_CREDENTIALS",
+                message: "Invalid username or password",
+            });
+        }
+    };
+
+    let (access_token, refresh_token) = issue_session_tokens(&username, &role, &secret)
+        .map_err(|e| {
+            log::error!("Failed to issue session tokens for '{}': {}", username, e);
+            ApiError::Internal { correlation_id: crate::utils::error_handler::generate_correlation_id() }
+        })?;
+    let (access_cookie, refresh_cookie) =
+        session_cookies(access_token, refresh_token, enable_tls.0);
+
+    AuditLog::new(
+        EventType::Authentication,
+        username.clone(),
+        Action::Read,
+        "session:login".to_string(),
+        AuditResult::Success,
+    )
+    .with_ip(ip)
+    .write(&chain, &pool).await;
+
+    log::info!("Operator '{}' logged in", username);
+
+    Ok(HttpResponse::Ok()
+        .cookie(access_cookie)
+        .cookie(refresh_cookie)
+        .json(serde_json::json!({ "username": username, "role": role })))
+}
+
+/// POST /auth/logout - Clear the operator's session cookies
+async fn logout(
+    req: actix_web::HttpRequest,
+    enable_tls: web::Data<TlsEnabled>,
+    pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
+) -> HttpResponse {
+    let ip = req.peer_addr().map(|a| a.ip());
+    let (access_cookie, refresh_cookie) = logout_cookies(enable_tls.0);
+
+    AuditLog::new(
+        EventType::Authentication,
+        "internal".to_string(),
+        Action::Read,
+        "session:logout".to_string(),
+        AuditResult::Success,
+    )
+    .with_ip(ip)
+    .write(&chain, &pool).await;
+
+    HttpResponse::Ok()
+        .cookie(access_cookie)
+        .cookie(refresh_cookie)
+        .json(serde_json::json!({ "status": "logged_out" }))
+}
+
+/// POST /auth/refresh - Exchange a valid refresh token cookie for a fresh
+/// access/refresh pair
+///
+/// Re-checks [`OperatorRegistry::role_for`] rather than trusting the role
+/// embedded in the refresh token, so a role change since login takes effect
+/// on the next refresh instead of only at the next full login.
+async fn refresh(
+    req: actix_web::HttpRequest,
+    registry: web::Data<OperatorRegistry>,
+    secret: web::Data<SessionSecret>,
+    enable_tls: web::Data<TlsEnabled>,
+    pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
+) -> Result<HttpResponse, ApiError> {
+    let ip = req.peer_addr().map(|a| a.ip());
+
+    let refresh_token = req
+        .cookie(crate::middleware::session::REFRESH_COOKIE_NAME)
+        .map(|c| c.value().to_string())
+        .ok_or(ApiError::Unauthorized {
+            code: "MISSING_REFRESH_TOKEN",
+            message: "Missing refresh token",
+        })?;
+
+    let claims = decode_refresh_token(&refresh_token, &secret).map_err(|e| {
+        log::warn!("Rejected refresh token: {}", e);
+        ApiError::Unauthorized {
+            code: "INVALID_REFRESH_TOKEN",
+            message: "Invalid or expired refresh token",
+        }
+    })?;
+
+    let role = registry.role_for(&claims.sub).ok_or(ApiError::Unauthorized {
+        code: "INVALID_REFRESH_TOKEN",
+        message: "Invalid or expired refresh token",
+    })?;
+
+    let (access_token, new_refresh_token) = issue_session_tokens(&claims.sub, &role, &secret)
+        .map_err(|e| {
+            log::error!("Failed to issue session tokens for '{}': {}", claims.sub, e);
+            ApiError::Internal { correlation_id: crate::utils::error_handler::generate_correlation_id() }
+        })?;
+    let (access_cookie, refresh_cookie) =
+        session_cookies(access_token, new_refresh_token, enable_tls.0);
+
+    AuditLog::new(
+        EventType::Authentication,
+        claims.sub.clone(),
+        Action::Read,
+        "session:refresh".to_string(),
+        AuditResult::Success,
+    )
+    .with_ip(ip)
+    .write(&chain, &pool).await;
+
+    Ok(HttpResponse::Ok()
+        .cookie(access_cookie)
+        .cookie(refresh_cookie)
+        .json(serde_json::json!({ "username": claims.sub, "role": role })))
+}
+
+/// Configure operator session routes
+///
+/// Routes:
+/// - POST /auth/login - Exchange operator credentials for a session
+/// - POST /auth/logout - Clear the session cookies
+/// - POST /auth/refresh - Renew a session from its refresh token cookie
+pub fn configure_auth(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/auth")
+            .route("/login", web::post().to(login))
+            .route("/logout", web::post().to(logout))
+            .route("/refresh", web::post().to(refresh)),
+    );
+}
@@ -1,62 +1,100 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse};
 use serde::Deserialize;
-use crate::database::{DbPool, queries};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use crate::database;
+use crate::middleware::session::AuthenticatedOperator;
+use crate::models::Case;
+use crate::openapi::ErrorResponse;
+use crate::utils::ApiError;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateCaseRequest {
     pub case_number: String,
     pub status: String,
     pub description: Option<String>,
 }
 
-pub async fn get_all_cases(pool: web::Data<DbPool>) -> impl Responder {
-    match queries::get_all_cases(pool.get_ref()).await {
-        Ok(cases) => HttpResponse::Ok().json(cases),
-        Err(e) => {
-            eprintln!("Database error: {:?}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch cases"
-            }))
-        }
-    }
+/// GET /cases - Retrieve all cases
+#[utoipa::path(
+    get,
+    path = "/cases",
+    tag = "cases",
+    responses(
+        (status = 200, description = "List of cases", body = [Case]),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+    ),
+    security(("operator_session" = []))
+)]
+pub(crate) async fn get_all_cases(
+    pool: web::Data<PgPool>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
+    let cases = database::get_all_cases(&pool).await?;
+    log::info!("Retrieved {} cases", cases.len());
+    Ok(HttpResponse::Ok().json(cases))
 }
 
-pub async fn get_case_by_id(
-    pool: web::Data<DbPool>,
-    case_id: web::Path<i32>
-) -> impl Responder {
-    match queries::get_case_by_id(pool.get_ref(), case_id.into_inner()).await {
-        Ok(case) => HttpResponse::Ok().json(case),
-        Err(sqlx::Error::RowNotFound) => {
-            HttpResponse::NotFound().json(serde_json::json!({
-                "error": "Case not found"
-            }))
-        }
-        Err(e) => {
-            eprintln!("Database error: {:?}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch case"
-            }))
+/// GET /cases/{id} - Retrieve a case by ID
+#[utoipa::path(
+    get,
+    path = "/cases/{id}",
+    tag = "cases",
+    params(("id" = i32, Path, description = "Case database ID")),
+    responses(
+        (status = 200, description = "Case found", body = Case),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 404, description = "No case with that ID", body = ErrorResponse),
+    ),
+    security(("operator_session" = []))
+)]
+pub(crate) async fn get_case_by_id(
+    pool: web::Data<PgPool>,
+    case_id: web::Path<i32>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
+    let case_id = case_id.into_inner();
+
+    match database::get_case_by_id(&pool, case_id).await? {
+        Some(case) => {
+            log::info!("Retrieved case with ID {}", case_id);
+            Ok(HttpResponse::Ok().json(case))
         }
+        None => Err(ApiError::NotFound { resource: "case" }),
     }
 }
 
-pub async fn create_case(
-    pool: web::Data<DbPool>,
-    request: web::Json<CreateCaseRequest>
-) -> impl Responder {
-    match queries::create_case(
-        pool.get_ref(),
-        request.case_number.clone(),
-        request.status.clone(),
-        request.description.clone()
-    ).await {
-        Ok(case) => HttpResponse::Created().json(case),
-        Err(e) => {
-            eprintln!("Database error: {:?}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to create case"
-            }))
-        }
-    }
-}
\ No newline at end of file
+/// POST /cases - Create a new case
+#[utoipa::path(
+    post,
+    path = "/cases",
+    tag = "cases",
+    request_body = CreateCaseRequest,
+    responses(
+        (status = 201, description = "Case created", body = Case),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 403, description = "Missing or invalid CSRF token", body = ErrorResponse),
+        (status = 409, description = "A case with that case number already exists", body = ErrorResponse),
+    ),
+    security(("operator_session" = []), ("csrf_token" = []))
+)]
+pub(crate) async fn create_case(
+    pool: web::Data<PgPool>,
+    request: web::Json<CreateCaseRequest>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
+    let request = request.into_inner();
+    let case = database::create_case(&pool, request.case_number, request.status, request.description).await?;
+    log::info!("Created case with ID {}", case.id);
+    Ok(HttpResponse::Created().json(case))
+}
+
+/// Configure all case-related routes
+pub fn configure_cases(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/cases")
+            .route("", web::get().to(get_all_cases))
+            .route("", web::post().to(create_case))
+            .route("/{id}", web::get().to(get_case_by_id)),
+    );
+}
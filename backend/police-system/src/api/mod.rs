@@ -1,6 +1,12 @@
 pub mod suspects;
 pub mod shared;
+pub mod cases;
+pub mod auth;
+pub mod audit;
 
 // Re-export configuration functions
 pub use suspects::configure_suspects;
-pub use shared::configure_shared;
\ No newline at end of file
+pub use shared::{configure_shared, configure_shared_token};
+pub use cases::configure_cases;
+pub use auth::configure_auth;
+pub use audit::configure_audit;
\ No newline at end of file
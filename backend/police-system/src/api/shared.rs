@@ -1,31 +1,174 @@
 use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use utoipa::ToSchema;
 use crate::database;
-use crate::utils::logging::hash_for_logging;
+use crate::middleware::auth::verify_api_key;
+use crate::middleware::jwt_auth::{issue_shared_api_token, JwtSecret, SharedApiClaims, SharedApiJwtTtlSeconds};
+use crate::openapi::ErrorResponse;
+use crate::utils::logging::{hash_for_logging, LoggingPepper};
 use crate::utils::error_handler::{
     handle_database_error,
     handle_not_found,
     handle_validation_error,
 };
 use crate::models::Suspect;
-use crate::utils::audit::{AuditLog, EventType, Action, AuditResult, extract_actor_from_request};
+use crate::utils::audit::{AuditChain, AuditLog, EventType, Action, AuditResult, extract_actor_from_request};
+use crate::utils::PageParams;
+
+/// Scope required by `GET /api/shared/suspects/{personal_id}` when the
+/// caller authenticated with a scoped JWT rather than an HTTP Signature.
+const SCOPE_SUSPECTS_READ: &str = "suspects:read";
+/// Scope required by `GET /api/shared/suspects`.
+const SCOPE_SUSPECTS_LIST: &str = "suspects:list";
+
+/// Scopes `POST /api/shared/token` is allowed to mint for a given `subject`,
+/// keyed the same way `issue_shared_api_token`'s `subject` argument is.
+/// `HOSPITAL_API_KEY` identifies exactly one caller today, so there's only
+/// one entry - but it's still a fixed, server-side ceiling rather than
+/// trusting the request body, so a caller can only ever narrow what it asks
+/// for, never broaden it beyond what it's actually entitled to.
+fn allowed_scopes_for(subject: &str) -> &'static [&'static str] {
+    match subject {
+        "hospital_system" => &[SCOPE_SUSPECTS_READ, SCOPE_SUSPECTS_LIST],
+        _ => &[],
+    }
+}
+
+/// A caller authenticated via `POST /api/shared/token` is only let through
+/// if its token carries `required_scope` - a caller authenticated via
+/// `Signature` instead (no [`SharedApiClaims`] in extensions) already
+/// cleared the Casbin RBAC check in [`crate::middleware::http_signature`],
+/// so it's waved through here unconditionally.
+fn check_scope(req: &actix_web::HttpRequest, required_scope: &str) -> Result<(), HttpResponse> {
+    match req.extensions().get::<SharedApiClaims>() {
+        Some(claims) if claims.has_scope(required_scope) => Ok(()),
+        Some(_) => Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": format!("Token missing required scope: {}", required_scope),
+            "code": "SCOPE_MISSING",
+        }))),
+        None => Ok(()),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TokenRequest {
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct TokenResponse {
+    token: String,
+    expires_in: i64,
+}
+
+/// POST /api/shared/token - Exchange the long-lived `X-API-Key` for a
+/// short-lived, scoped JWT
+///
+/// Deliberately mounted outside the `HttpSignatureAuth`/`JwtAuth` wrapped
+/// scope (a caller can't present a bearer token it doesn't have yet), and
+/// gated instead by `verify_api_key` - the same `HOSPITAL_API_KEY` check
+/// `ApiKeyAuth` used before it was replaced by HTTP Signatures.
+#[utoipa::path(
+    post,
+    path = "/api/shared/token",
+    tag = "shared",
+    request_body = TokenRequest,
+    responses(
+        (status = 200, description = "Scoped JWT issued", body = TokenResponse),
+        (status = 401, description = "Missing or invalid X-API-Key", body = ErrorResponse),
+        (status = 500, description = "Failed to issue token", body = ErrorResponse),
+    ),
+    security(("hospital_api_key" = []))
+)]
+pub async fn issue_token(
+    req: actix_web::HttpRequest,
+    request: web::Json<TokenRequest>,
+    secret: web::Data<JwtSecret>,
+    ttl: web::Data<SharedApiJwtTtlSeconds>,
+) -> HttpResponse {
+    if let Err(e) = verify_api_key(&req).await {
+        return e.error_response();
+    }
+
+    // HOSPITAL_API_KEY identifies exactly one caller today, so the minted
+    // subject matches the "hospital_system" role grant already present in
+    // rbac/policy.csv.
+    let subject = "hospital_system";
+
+    // Intersect the requested scopes against what `subject` is actually
+    // entitled to - the request body is caller-controlled, so it can only
+    // ever narrow the grant, never broaden it beyond `allowed_scopes_for`.
+    let allowed = allowed_scopes_for(subject);
+    let scopes: Vec<String> = request
+        .into_inner()
+        .scopes
+        .into_iter()
+        .filter(|s| allowed.contains(&s.as_str()))
+        .collect();
+
+    match issue_shared_api_token(subject, scopes, &secret, ttl.0) {
+        Ok(token) => HttpResponse::Ok().json(TokenResponse { token, expires_in: ttl.0 }),
+        Err(e) => {
+            log::error!("Failed to issue shared API token: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to issue token",
+                "code": "TOKEN_ISSUE_FAILED",
+            }))
+        }
+    }
+}
+
+/// A keyset-paginated page of suspects, modeled on object-store list APIs:
+/// `next_cursor` is the `id` of the last row in this page, or `null` once
+/// the caller has reached the end of the table. `total` counts the whole
+/// `suspects` table regardless of cursor, so the hospital importer can size
+/// its work without paging through everything first.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuspectPage {
+    pub items: Vec<Suspect>,
+    pub next_cursor: Option<i32>,
+    pub total: i64,
+}
 
 /// GET /api/shared/suspects/{personal_id} - Retrieve suspect info by Swedish personal ID
-/// 
+///
 /// This endpoint allows the hospital system to check if a suspect has a police record
 /// by querying their personal_id (Swedish format: YYYYMMDD-XXXX)
-/// 
-/// SECURITY: Requires valid API key in X-API-Key header
-async fn get_shared_suspect_info(
+///
+/// SECURITY: Requires an RSA-SHA256 `Signature` header or a scoped JWT
+/// carrying `suspects:read` (see `POST /api/shared/token`)
+#[utoipa::path(
+    get,
+    path = "/api/shared/suspects/{personal_id}",
+    tag = "shared",
+    params(("personal_id" = String, Path, description = "Swedish personal ID (YYYYMMDD-XXXX)")),
+    responses(
+        (status = 200, description = "Suspect found", body = Suspect),
+        (status = 400, description = "Invalid personal_id format", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid Signature/JWT"),
+        (status = 403, description = "Not authorized to perform this action", body = ErrorResponse),
+        (status = 404, description = "No suspect with that personal ID"),
+    ),
+    security(("shared_api_auth" = []))
+)]
+pub(crate) async fn get_shared_suspect_info(
     pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
     personal_id: web::Path<String>,
     req: actix_web::HttpRequest,
+    pepper: web::Data<LoggingPepper>,
 ) -> HttpResponse {
+    if let Err(response) = check_scope(&req, SCOPE_SUSPECTS_READ) {
+        return response;
+    }
+
     let pid = personal_id.into_inner();
-    let resource_hash = hash_for_logging(&pid);
+    let resource_hash = hash_for_logging(&pid, &pepper);
     let actor = extract_actor_from_request(&req);
     let ip = req.peer_addr().map(|a| a.ip());
-    
+
     // Validate personal ID format
     if !Suspect::validate_personal_id(&pid) {
         AuditLog::new(
@@ -37,7 +180,7 @@ async fn get_shared_suspect_info(
         )
         .with_ip(ip)
         .with_details("Invalid personal_id format".to_string())
-        .write();
+        .write(&chain, &pool).await;
         
         return handle_validation_error(
             &format!("Invalid personal_id format: {}", resource_hash),
@@ -58,7 +201,7 @@ async fn get_shared_suspect_info(
                 AuditResult::Success,
             )
             .with_ip(ip)
-            .write();
+            .write(&chain, &pool).await;
             
             log::info!("Shared API: Found suspect record for personal_id hash: {}", 
                 resource_hash);
@@ -74,7 +217,7 @@ async fn get_shared_suspect_info(
             )
             .with_ip(ip)
             .with_details("Suspect not found".to_string())
-            .write();
+            .write(&chain, &pool).await;
             
             log::info!("Shared API: No suspect record found for personal_id hash: {}", 
                 resource_hash);
@@ -90,76 +233,135 @@ async fn get_shared_suspect_info(
             )
             .with_ip(ip)
             .with_details(format!("Database error: {}", e))
-            .write();
+            .write(&chain, &pool).await;
             
             handle_database_error(e, "get_shared_suspect_info")
         }
     }
 }
 
-/// GET /api/shared/suspects - Retrieve all suspects
-/// 
-/// This endpoint allows the hospital system to retrieve a complete list of all suspects
-/// for cross-referencing with their patient database
-/// 
-/// SECURITY: Requires valid API key in X-API-Key header
-async fn get_all_shared_suspects(
+/// GET /api/shared/suspects - Retrieve a page of suspects
+///
+/// This endpoint allows the hospital system to page through suspects for
+/// cross-referencing with their patient database, instead of the whole
+/// table being serialized in one response.
+///
+/// SECURITY: Requires an RSA-SHA256 `Signature` header or a scoped JWT
+/// carrying `suspects:list` (see `POST /api/shared/token`)
+#[utoipa::path(
+    get,
+    path = "/api/shared/suspects",
+    tag = "shared",
+    params(
+        ("limit" = Option<i64>, Query, description = "Max rows to return (default 50, max 500)"),
+        ("after" = Option<i32>, Query, description = "Resume after this suspect id"),
+    ),
+    responses(
+        (status = 200, description = "A page of suspects", body = SuspectPage),
+        (status = 401, description = "Missing or invalid Signature/JWT"),
+        (status = 403, description = "Not authorized to perform this action", body = ErrorResponse),
+    ),
+    security(("shared_api_auth" = []))
+)]
+pub(crate) async fn get_all_shared_suspects(
     pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
+    page: web::Query<PageParams>,
     req: actix_web::HttpRequest,
 ) -> HttpResponse {
+    if let Err(response) = check_scope(&req, SCOPE_SUSPECTS_LIST) {
+        return response;
+    }
+
     let actor = extract_actor_from_request(&req);
     let ip = req.peer_addr().map(|a| a.ip());
-    
-    log::info!("Shared API: Hospital system requesting all suspects");
-    
-    match database::get_all_suspects(&pool).await {
+    let limit = page.limit();
+    let after_id = page.after_id();
+
+    log::info!("Shared API: Hospital system requesting suspects page (limit={}, after={})", limit, after_id);
+
+    match database::get_suspects_page(&pool, limit, after_id).await {
         Ok(suspects) => {
+            let total = match database::count_suspects(&pool).await {
+                Ok(total) => total,
+                Err(e) => {
+                    AuditLog::new(
+                        EventType::SharedApiAccess,
+                        actor,
+                        Action::Read,
+                        "suspects:page".to_string(),
+                        AuditResult::Failure,
+                    )
+                    .with_ip(ip)
+                    .with_details(format!("Database error: {}", e))
+                    .write(&chain, &pool).await;
+
+                    return handle_database_error(e, "get_all_shared_suspects");
+                }
+            };
+
             AuditLog::new(
                 EventType::SharedApiAccess,
                 actor,
                 Action::Read,
-                format!("suspects:all (count: {})", suspects.len()),
+                format!("suspects:page (limit={}, after={}, returned={})", limit, after_id, suspects.len()),
                 AuditResult::Success,
             )
             .with_ip(ip)
-            .write();
-            
+            .write(&chain, &pool).await;
+
             log::info!("Shared API: Returning {} suspect records", suspects.len());
-            HttpResponse::Ok().json(suspects)
+            let next_cursor = if suspects.len() as i64 == limit {
+                suspects.last().map(|s| s.id)
+            } else {
+                None
+            };
+            HttpResponse::Ok().json(SuspectPage { items: suspects, next_cursor, total })
         }
         Err(e) => {
             AuditLog::new(
                 EventType::SharedApiAccess,
                 actor,
                 Action::Read,
-                "suspects:all".to_string(),
+                "suspects:page".to_string(),
                 AuditResult::Failure,
             )
             .with_ip(ip)
             .with_details(format!("Database error: {}", e))
-            .write();
-            
+            .write(&chain, &pool).await;
+
             handle_database_error(e, "get_all_shared_suspects")
         }
     }
 }
 
 /// Configure shared/inter-system API routes
-/// 
+///
 /// These endpoints are designed to be called by the hospital system
 /// to check if suspects have police records.
-/// 
+///
 /// Routes:
-/// - GET /api/shared/suspects - List all suspects (requires API key)
-/// - GET /api/shared/suspects/{personal_id} - Check specific person (requires API key)
-/// 
+/// - GET /api/shared/suspects - List a page of suspects (requires Signature or scoped JWT)
+/// - GET /api/shared/suspects/{personal_id} - Check specific person (requires Signature or scoped JWT)
+///
 /// # Security
-/// 
-/// All endpoints require API key authentication via X-API-Key header.
+///
+/// Callers authenticate with either an RSA-SHA256 `Signature` header (see
+/// `middleware::http_signature`) or a scoped JWT (see `middleware::jwt_auth`)
+/// minted by `POST /api/shared/token`; see [`configure_shared_token`] for
+/// that endpoint, which is mounted separately since it isn't itself behind
+/// either of those guards.
 /// Rate limiting is applied at the application level.
 /// Input validation enforces Swedish personal ID format.
 pub fn configure_shared(cfg: &mut web::ServiceConfig) {
     cfg
         .route("/suspects", web::get().to(get_all_shared_suspects))
         .route("/suspects/{personal_id}", web::get().to(get_shared_suspect_info));
+}
+
+/// Configures `POST /api/shared/token` - deliberately a separate
+/// `ServiceConfig` function from [`configure_shared`] so `main.rs` can
+/// mount it outside the `HttpSignatureAuth`/`JwtAuth` wrapped scope.
+pub fn configure_shared_token(cfg: &mut web::ServiceConfig) {
+    cfg.route("/token", web::post().to(issue_token));
 }
\ No newline at end of file
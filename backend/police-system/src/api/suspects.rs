@@ -1,112 +1,145 @@
 use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 use sqlx::PgPool;
+use utoipa::ToSchema;
 use crate::database;
+use crate::middleware::session::AuthenticatedOperator;
 use crate::models::{CreateSuspect, UpdateSuspect, Suspect};
-use crate::utils::logging::hash_for_logging;
-use crate::utils::error_handler::{
-    handle_database_error,
-    handle_not_found,
-    handle_validation_error,
-};
-use crate::utils::audit::{AuditLog, EventType, Action, AuditResult};
+use crate::openapi::ErrorResponse;
+use crate::utils::logging::{hash_for_logging, LoggingPepper};
+use crate::utils::error_handler::handle_validation_error;
+use crate::utils::ApiError;
+use crate::utils::audit::{AuditChain, AuditLog, EventType, Action, AuditResult};
+use crate::utils::ValidatedJson;
 
 /// Request body for flag updates - now includes personal_id
-#[derive(Deserialize)]
-struct FlagUpdateRequest {
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct FlagUpdateRequest {
     personal_id: String,
     flag: bool,
 }
 
 /// GET /suspects - Retrieve all suspects
-async fn get_all_suspects(pool: web::Data<PgPool>) -> HttpResponse {
-    match database::get_all_suspects(&pool).await {
-        Ok(suspects) => {
-            log::info!("Retrieved {} suspects", suspects.len());
-            HttpResponse::Ok().json(suspects)
-        }
-        Err(e) => handle_database_error(e, "get_all_suspects"),
-    }
+#[utoipa::path(
+    get,
+    path = "/suspects",
+    tag = "suspects",
+    responses(
+        (status = 200, description = "List of suspects", body = [Suspect]),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+    ),
+    security(("operator_session" = []))
+)]
+pub(crate) async fn get_all_suspects(
+    pool: web::Data<PgPool>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
+    let suspects = database::get_all_suspects(&pool).await?;
+    log::info!("Retrieved {} suspects", suspects.len());
+    Ok(HttpResponse::Ok().json(suspects))
 }
 
 /// GET /suspects/{id} - Retrieve a suspect by ID
-async fn get_suspect_by_id(
+#[utoipa::path(
+    get,
+    path = "/suspects/{id}",
+    tag = "suspects",
+    params(("id" = i32, Path, description = "Suspect database ID")),
+    responses(
+        (status = 200, description = "Suspect found", body = Suspect),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 404, description = "No suspect with that ID", body = ErrorResponse),
+    ),
+    security(("operator_session" = []))
+)]
+pub(crate) async fn get_suspect_by_id(
     pool: web::Data<PgPool>,
     id: web::Path<i32>,
-) -> HttpResponse {
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
     let suspect_id = id.into_inner();
-    
-    match database::get_suspect_by_id(&pool, suspect_id).await {
-        Ok(Some(suspect)) => {
+
+    match database::get_suspect_by_id(&pool, suspect_id).await? {
+        Some(suspect) => {
             log::info!("Retrieved suspect with ID {}", suspect_id);
-            HttpResponse::Ok().json(suspect)
+            Ok(HttpResponse::Ok().json(suspect))
         }
-        Ok(None) => handle_not_found("suspect", &suspect_id.to_string()),
-        Err(e) => handle_database_error(e, "get_suspect_by_id"),
+        None => Err(ApiError::NotFound { resource: "suspect" }),
     }
 }
 
 /// GET /suspects/personal/{personal_id} - Retrieve a suspect by Swedish personal ID
-async fn get_suspect_by_personal_id(
+#[utoipa::path(
+    get,
+    path = "/suspects/personal/{personal_id}",
+    tag = "suspects",
+    params(("personal_id" = String, Path, description = "Swedish personal ID (YYYYMMDD-XXXX)")),
+    responses(
+        (status = 200, description = "Suspect found", body = Suspect),
+        (status = 400, description = "Invalid personal_id format", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 404, description = "No suspect with that personal ID", body = ErrorResponse),
+    ),
+    security(("operator_session" = []))
+)]
+pub(crate) async fn get_suspect_by_personal_id(
     pool: web::Data<PgPool>,
     personal_id: web::Path<String>,
-) -> HttpResponse {
+    pepper: web::Data<LoggingPepper>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
     let pid = personal_id.into_inner();
-    
+
     // Validate personal ID format
     if !Suspect::validate_personal_id(&pid) {
-        return handle_validation_error(
-            &format!("Invalid personal_id format: {}", hash_for_logging(&pid)),
-            "get_suspect_by_personal_id"
-        );
+        return Ok(handle_validation_error(
+            &format!("Invalid personal_id format: {}", hash_for_logging(&pid, &pepper)),
+            "get_suspect_by_personal_id",
+        ));
     }
-    
-    match database::get_suspect_by_personal_id(&pool, &pid).await {
-        Ok(Some(suspect)) => {
-            log::info!("Retrieved suspect with personal_id hash: {}", hash_for_logging(&pid));
-            HttpResponse::Ok().json(suspect)
+
+    match database::get_suspect_by_personal_id(&pool, &pid).await? {
+        Some(suspect) => {
+            log::info!("Retrieved suspect with personal_id hash: {}", hash_for_logging(&pid, &pepper));
+            Ok(HttpResponse::Ok().json(suspect))
         }
-        Ok(None) => handle_not_found("suspect", &hash_for_logging(&pid)),
-        Err(e) => handle_database_error(e, "get_suspect_by_personal_id"),
+        None => Err(ApiError::NotFound { resource: "suspect" }),
     }
 }
 
 /// POST /suspects - Create a new suspect
-async fn create_suspect(
+#[utoipa::path(
+    post,
+    path = "/suspects",
+    tag = "suspects",
+    request_body = CreateSuspect,
+    responses(
+        (status = 201, description = "Suspect created", body = Suspect),
+        (status = 400, description = "Invalid suspect payload", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 403, description = "Missing or invalid CSRF token", body = ErrorResponse),
+        (status = 409, description = "A suspect with that personal ID already exists", body = ErrorResponse),
+    ),
+    security(("operator_session" = []), ("csrf_token" = []))
+)]
+pub(crate) async fn create_suspect(
     pool: web::Data<PgPool>,
-    suspect: web::Json<CreateSuspect>,
+    chain: web::Data<AuditChain>,
+    suspect: ValidatedJson<CreateSuspect>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+    pepper: web::Data<LoggingPepper>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
     let suspect_data = suspect.into_inner();
-    let resource_hash = hash_for_logging(&suspect_data.personal_id);
-    
-    // Validate personal ID format
-    if !Suspect::validate_personal_id(&suspect_data.personal_id) {
-        // Audit failure
-        AuditLog::new(
-            EventType::SuspectCreate,
-            "internal".to_string(),
-            Action::Create,
-            format!("suspect:{}", resource_hash),
-            AuditResult::Failure,
-        )
-        .with_ip(req.peer_addr().map(|a| a.ip()))
-        .with_details("Invalid personal_id format".to_string())
-        .write();
-        
-        return handle_validation_error(
-            &format!("Invalid personal_id format: {}", resource_hash),
-            "create_suspect"
-        );
-    }
-    
+    let resource_hash = hash_for_logging(&suspect_data.personal_id, &pepper);
+
     match database::create_suspect(&pool, suspect_data).await {
         Ok(created_suspect) => {
             let pid_hash = created_suspect.personal_id
                 .as_ref()
-                .map(|pid| hash_for_logging(pid))
+                .map(|pid| hash_for_logging(pid, &pepper))
                 .unwrap_or_else(|| "unknown".to_string());
-            
+
             // Audit success
             AuditLog::new(
                 EventType::SuspectCreate,
@@ -116,11 +149,11 @@ async fn create_suspect(
                 AuditResult::Success,
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
-            .write();
-            
-            log::info!("Created suspect with ID {} (personal_id hash: {})", 
+            .write(&chain, &pool).await;
+
+            log::info!("Created suspect with ID {} (personal_id hash: {})",
                 created_suspect.id, pid_hash);
-            HttpResponse::Created().json(created_suspect)
+            Ok(HttpResponse::Created().json(created_suspect))
         }
         Err(e) => {
             // Audit failure
@@ -133,50 +166,52 @@ async fn create_suspect(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details(format!("Database error: {}", e))
-            .write();
-            
-            handle_database_error(e, "create_suspect")
+            .write(&chain, &pool).await;
+
+            Err(ApiError::from(e))
         }
     }
 }
 
 /// PUT /suspects/{id} - Update an existing suspect
-async fn update_suspect(
+#[utoipa::path(
+    put,
+    path = "/suspects/{id}",
+    tag = "suspects",
+    params(("id" = i32, Path, description = "Suspect database ID")),
+    request_body = UpdateSuspect,
+    responses(
+        (status = 200, description = "Suspect updated", body = Suspect),
+        (status = 400, description = "Invalid suspect payload", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 403, description = "Missing or invalid CSRF token", body = ErrorResponse),
+        (status = 404, description = "No suspect with that ID", body = ErrorResponse),
+    ),
+    security(("operator_session" = []), ("csrf_token" = []))
+)]
+pub(crate) async fn update_suspect(
     pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
     id: web::Path<i32>,
-    suspect: web::Json<UpdateSuspect>,
+    suspect: ValidatedJson<UpdateSuspect>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+    pepper: web::Data<LoggingPepper>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
     let suspect_id = id.into_inner();
     let suspect_data = suspect.into_inner();
-    let resource_hash = hash_for_logging(&suspect_data.personal_id);
-    
-    // Validate personal ID format if provided
-    if !Suspect::validate_personal_id(&suspect_data.personal_id) {
-        AuditLog::new(
-            EventType::SuspectUpdate,
-            "internal".to_string(),
-            Action::Update,
-            format!("suspect:{}", resource_hash),
-            AuditResult::Failure,
-        )
-        .with_ip(req.peer_addr().map(|a| a.ip()))
-        .with_details("Invalid personal_id format".to_string())
-        .write();
-        
-        return handle_validation_error(
-            &format!("Invalid personal_id format: {}", resource_hash),
-            "update_suspect"
-        );
-    }
-    
+    let resource_hash = suspect_data.personal_id
+        .as_deref()
+        .map(|pid| hash_for_logging(pid, &pepper))
+        .unwrap_or_else(|| "unknown".to_string());
+
     match database::update_suspect(&pool, suspect_id, suspect_data).await {
         Ok(Some(updated_suspect)) => {
             let pid_hash = updated_suspect.personal_id
                 .as_ref()
-                .map(|pid| hash_for_logging(pid))
+                .map(|pid| hash_for_logging(pid, &pepper))
                 .unwrap_or_else(|| "unknown".to_string());
-            
+
             AuditLog::new(
                 EventType::SuspectUpdate,
                 "internal".to_string(),
@@ -185,10 +220,10 @@ async fn update_suspect(
                 AuditResult::Success,
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
-            .write();
-            
+            .write(&chain, &pool).await;
+
             log::info!("Updated suspect with ID {}", suspect_id);
-            HttpResponse::Ok().json(updated_suspect)
+            Ok(HttpResponse::Ok().json(updated_suspect))
         }
         Ok(None) => {
             AuditLog::new(
@@ -200,9 +235,9 @@ async fn update_suspect(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details("Suspect not found".to_string())
-            .write();
-            
-            handle_not_found("suspect", &suspect_id.to_string())
+            .write(&chain, &pool).await;
+
+            Err(ApiError::NotFound { resource: "suspect" })
         }
         Err(e) => {
             AuditLog::new(
@@ -214,21 +249,36 @@ async fn update_suspect(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details(format!("Database error: {}", e))
-            .write();
-            
-            handle_database_error(e, "update_suspect")
+            .write(&chain, &pool).await;
+
+            Err(ApiError::from(e))
         }
     }
 }
 
 /// DELETE /suspects/{id} - Delete a suspect
-async fn delete_suspect(
+#[utoipa::path(
+    delete,
+    path = "/suspects/{id}",
+    tag = "suspects",
+    params(("id" = i32, Path, description = "Suspect database ID")),
+    responses(
+        (status = 204, description = "Suspect deleted"),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 403, description = "Missing or invalid CSRF token", body = ErrorResponse),
+        (status = 404, description = "No suspect with that ID", body = ErrorResponse),
+    ),
+    security(("operator_session" = []), ("csrf_token" = []))
+)]
+pub(crate) async fn delete_suspect(
     pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
     id: web::Path<i32>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
     let suspect_id = id.into_inner();
-    
+
     match database::delete_suspect(&pool, suspect_id).await {
         Ok(true) => {
             AuditLog::new(
@@ -239,10 +289,10 @@ async fn delete_suspect(
                 AuditResult::Success,
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
-            .write();
-            
+            .write(&chain, &pool).await;
+
             log::info!("Deleted suspect with ID {}", suspect_id);
-            HttpResponse::NoContent().finish()
+            Ok(HttpResponse::NoContent().finish())
         }
         Ok(false) => {
             AuditLog::new(
@@ -254,9 +304,9 @@ async fn delete_suspect(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details("Suspect not found".to_string())
-            .write();
-            
-            handle_not_found("suspect", &suspect_id.to_string())
+            .write(&chain, &pool).await;
+
+            Err(ApiError::NotFound { resource: "suspect" })
         }
         Err(e) => {
             AuditLog::new(
@@ -268,27 +318,44 @@ async fn delete_suspect(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details(format!("Database error: {}", e))
-            .write();
-            
-            handle_database_error(e, "delete_suspect")
+            .write(&chain, &pool).await;
+
+            Err(ApiError::from(e))
         }
     }
 }
 
 /// POST /suspects/flag - Update flag status
-/// 
+///
 /// SECURITY IMPROVEMENT: Moved personal_id from URL path to request body
 /// to prevent logging of sensitive data in browser history and server logs.
-/// 
+///
 /// This triggers automatic synchronization to the hospital database via postgres_fdw
-async fn update_flag(
+#[utoipa::path(
+    post,
+    path = "/suspects/flag",
+    tag = "suspects",
+    request_body = FlagUpdateRequest,
+    responses(
+        (status = 200, description = "Flag updated", body = Suspect),
+        (status = 400, description = "Invalid personal_id format", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid operator session", body = ErrorResponse),
+        (status = 403, description = "Missing or invalid CSRF token", body = ErrorResponse),
+        (status = 404, description = "No suspect with that personal ID", body = ErrorResponse),
+    ),
+    security(("operator_session" = []), ("csrf_token" = []))
+)]
+pub(crate) async fn update_flag(
     pool: web::Data<PgPool>,
+    chain: web::Data<AuditChain>,
     flag_data: web::Json<FlagUpdateRequest>,
     req: actix_web::HttpRequest,
-) -> HttpResponse {
+    pepper: web::Data<LoggingPepper>,
+    _operator: AuthenticatedOperator,
+) -> Result<HttpResponse, ApiError> {
     let request = flag_data.into_inner();
-    let resource_hash = hash_for_logging(&request.personal_id);
-    
+    let resource_hash = hash_for_logging(&request.personal_id, &pepper);
+
     // Validate personal ID format
     if !Suspect::validate_personal_id(&request.personal_id) {
         AuditLog::new(
@@ -300,14 +367,14 @@ async fn update_flag(
         )
         .with_ip(req.peer_addr().map(|a| a.ip()))
         .with_details("Invalid personal_id format".to_string())
-        .write();
-        
-        return handle_validation_error(
+        .write(&chain, &pool).await;
+
+        return Ok(handle_validation_error(
             &format!("Invalid personal_id format: {}", resource_hash),
-            "update_flag"
-        );
+            "update_flag",
+        ));
     }
-    
+
     match database::update_flag(&pool, &request.personal_id, request.flag).await {
         Ok(Some(updated_suspect)) => {
             AuditLog::new(
@@ -319,14 +386,14 @@ async fn update_flag(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details(format!("Flag updated to {}", request.flag))
-            .write();
-            
+            .write(&chain, &pool).await;
+
             log::info!(
                 "Updated flag to {} for suspect with personal_id hash: {} (will auto-sync to hospital)",
                 request.flag,
                 resource_hash
             );
-            HttpResponse::Ok().json(updated_suspect)
+            Ok(HttpResponse::Ok().json(updated_suspect))
         }
         Ok(None) => {
             AuditLog::new(
@@ -338,9 +405,9 @@ async fn update_flag(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details("Suspect not found".to_string())
-            .write();
-            
-            handle_not_found("suspect", &resource_hash)
+            .write(&chain, &pool).await;
+
+            Err(ApiError::NotFound { resource: "suspect" })
         }
         Err(e) => {
             AuditLog::new(
@@ -352,15 +419,15 @@ async fn update_flag(
             )
             .with_ip(req.peer_addr().map(|a| a.ip()))
             .with_details(format!("Database error: {}", e))
-            .write();
-            
-            handle_database_error(e, "update_flag")
+            .write(&chain, &pool).await;
+
+            Err(ApiError::from(e))
         }
     }
 }
 
 /// Configure all suspect-related routes
-/// 
+///
 /// Routes are ordered with literal paths first to avoid conflicts:
 /// - /suspects (GET, POST)
 /// - /suspects/flag (POST) - UPDATED: no longer includes personal_id in path
@@ -377,4 +444,4 @@ pub fn configure_suspects(cfg: &mut web::ServiceConfig) {
             .route("/{id}", web::put().to(update_suspect))
             .route("/{id}", web::delete().to(delete_suspect))
     );
-}
\ No newline at end of file
+}
@@ -1,21 +1,38 @@
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use std::env;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+/// Default `max_connections` when `DATABASE_MAX_CONNECTIONS` isn't set:
+/// 4 connections per available CPU, the same rule of thumb sqlx's own docs
+/// suggest for a CPU-bound connection pool, rather than a flat number that's
+/// either starved on a big box or wasteful on a small one.
+fn default_max_connections() -> u32 {
+    let cpus = std::thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1);
+    (cpus as u32) * 4
+}
 
 /// Establishes a connection pool to the PostgreSQL database
-/// 
+///
 /// Reads the DATABASE_URL from environment variables and creates
-/// a connection pool with a maximum of 5 connections.
-/// 
+/// a connection pool sized and timed out per the `DATABASE_*` variables
+/// below.
+///
 /// # Returns
-/// 
+///
 /// * `Result<PgPool, sqlx::Error>` - Connection pool on success, error on failure
-/// 
+///
 /// # Environment Variables
-/// 
+///
 /// * `DATABASE_URL` - PostgreSQL connection string (e.g., postgresql://postgres@localhost/police_db)
-/// 
+/// * `DATABASE_MAX_CONNECTIONS` - Pool size cap (default: 4 * available CPUs)
+/// * `DATABASE_ACQUIRE_TIMEOUT_SECONDS` - How long to wait for a free connection before erroring (default: 30)
+/// * `DATABASE_IDLE_TIMEOUT_SECONDS` - How long an idle connection may sit before being closed (default: 600)
+///
 /// # Example
-/// 
+///
 /// ```rust
 /// let pool = establish_connection().await?;
 /// ```
@@ -24,9 +41,22 @@ pub async fn establish_connection() -> Result<PgPool, sqlx::Error> {
     // Read database URL from environment
     let database_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set in .env file");
-    
+
+    let max_connections: u32 = env::var("DATABASE_MAX_CONNECTIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(default_max_connections);
+    let acquire_timeout_seconds: u64 = env::var("DATABASE_ACQUIRE_TIMEOUT_SECONDS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()
+        .unwrap_or(30);
+    let idle_timeout_seconds: u64 = env::var("DATABASE_IDLE_TIMEOUT_SECONDS")
+        .unwrap_or_else(|_| "600".to_string())
+        .parse()
+        .unwrap_or(600);
+
     log::info!("Attempting to connect to database...");
-    
+
     // Extract and log only the host (not credentials)
     if let Some(host_start) = database_url.find('@') {
         let host_part = &database_url[host_start + 1..];
@@ -34,10 +64,17 @@ pub async fn establish_connection() -> Result<PgPool, sqlx::Error> {
     } else {
         log::debug!("Connecting to database (local socket)");
     }
-    
+
+    log::info!(
+        "Database pool configuration: max_connections={}, acquire_timeout={}s, idle_timeout={}s",
+        max_connections, acquire_timeout_seconds, idle_timeout_seconds
+    );
+
     // Create connection pool with configuration
     let pool = PgPoolOptions::new()
-        .max_connections(5)
+        .max_connections(max_connections)
+        .acquire_timeout(Duration::from_secs(acquire_timeout_seconds))
+        .idle_timeout(Duration::from_secs(idle_timeout_seconds))
         .connect(&database_url)
         .await
         .map_err(|e| {
@@ -45,9 +82,9 @@ pub async fn establish_connection() -> Result<PgPool, sqlx::Error> {
             log::error!("Please verify DATABASE_URL is correct and PostgreSQL is running");
             e
         })?;
-    
+
     log::info!("Successfully established connection pool to police_db");
-    
+
     Ok(pool)
 }
 
@@ -0,0 +1,29 @@
+use sqlx::PgPool;
+use sqlx::migrate::Migrator;
+
+/// Embeds the SQL files under `migrations/` into the binary, mirroring the
+/// hospital system's migration runner.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Runs any pending migrations against `pool`, failing fast with a clear log
+/// line if a migration cannot be applied.
+pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    log::info!("Running database migrations...");
+
+    MIGRATOR.run(pool).await.map_err(|e| {
+        log::error!("❌ Failed to run database migrations: {}", e);
+        e
+    })?;
+
+    log::info!("✅ Database migrations up to date");
+    Ok(())
+}
+
+/// Returns true if automatic migrations should be skipped, e.g. on a
+/// read-only replica.
+pub fn should_skip_migrations() -> bool {
+    std::env::var("SKIP_AUTO_MIGRATIONS")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false)
+}
@@ -1,16 +1,25 @@
 pub mod connection;
 pub mod queries;
+pub mod migrations;
 
 // Re-export connection function
 pub use connection::establish_connection;
 
+// Re-export migration helpers
+pub use migrations::{run_migrations, should_skip_migrations};
+
 // Re-export all query functions
 pub use queries::{
     get_all_suspects,
+    get_suspects_page,
+    count_suspects,
     get_suspect_by_id,
     get_suspect_by_personal_id,
     create_suspect,
     update_suspect,
     delete_suspect,
     update_flag,
+    get_all_cases,
+    get_case_by_id,
+    create_case,
 };
\ No newline at end of file
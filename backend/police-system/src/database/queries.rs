@@ -1,5 +1,6 @@
 use sqlx::PgPool;
-use crate::models::{Suspect, CreateSuspect, UpdateSuspect};
+use crate::models::{Suspect, CreateSuspect, UpdateSuspect, Case};
+use crate::sync::outbox;
 
 /// Retrieves all suspects from the database
 /// 
@@ -21,6 +22,55 @@ pub async fn get_all_suspects(pool: &PgPool) -> Result<Vec<Suspect>, sqlx::Error
     Ok(suspects)
 }
 
+/// Retrieves up to `limit` suspects with `id > after_id`, ordered by `id`
+///
+/// Keyset (cursor) pagination for the shared API: the caller passes back
+/// the last `id` it saw as `after_id` to resume where it left off, instead
+/// of the whole table being serialized in one response.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `limit` - Maximum number of rows to return
+/// * `after_id` - Only rows with `id` greater than this are returned
+///
+/// # Returns
+///
+/// * `Result<Vec<Suspect>, sqlx::Error>` - Up to `limit` suspects
+pub async fn get_suspects_page(pool: &PgPool, limit: i64, after_id: i32) -> Result<Vec<Suspect>, sqlx::Error> {
+    let suspects = sqlx::query_as!(
+        Suspect,
+        "SELECT id, full_name, personal_id, flag FROM suspects WHERE id > $1 ORDER BY id LIMIT $2",
+        after_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(suspects)
+}
+
+/// Counts every row in the `suspects` table, regardless of `after_id`/`limit`
+///
+/// Paired with [`get_suspects_page`] so the shared API can report `total`
+/// alongside a single page, letting the hospital importer size its work
+/// without walking every cursor first.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// * `Result<i64, sqlx::Error>` - Total number of suspects
+pub async fn count_suspects(pool: &PgPool) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query!("SELECT COUNT(*) as \"count!\" FROM suspects")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.count)
+}
+
 /// Retrieves a suspect by their database ID
 /// 
 /// # Arguments
@@ -160,38 +210,126 @@ pub async fn delete_suspect(pool: &PgPool, id: i32) -> Result<bool, sqlx::Error>
     Ok(result.rows_affected() > 0)
 }
 
-/// Updates the flag status of a suspect by personal ID
-/// This function is particularly important for cross-system synchronization
-/// 
+/// Updates the flag status of a suspect by personal ID, and queues the
+/// change for delivery to the hospital system.
+///
+/// This function is particularly important for cross-system synchronization:
+/// the flag update and the `sync_outbox` insert happen in the same
+/// transaction, so a crash or dropped HTTP call can never silently desync
+/// the two databases - the sync worker delivers the queued event separately.
+///
 /// # Arguments
-/// 
+///
 /// * `pool` - Database connection pool
 /// * `personal_id` - Swedish personal ID (YYYYMMDD-XXXX)
 /// * `flag` - New flag status
-/// 
+///
 /// # Returns
-/// 
+///
 /// * `Result<Option<Suspect>, sqlx::Error>` - Updated suspect if found, None otherwise
 pub async fn update_flag(
     pool: &PgPool,
     personal_id: &str,
     flag: bool,
 ) -> Result<Option<Suspect>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
     let updated_suspect = sqlx::query_as!(
         Suspect,
-        "UPDATE suspects 
-         SET flag = $1 
+        "UPDATE suspects
+         SET flag = $1
          WHERE personal_id = $2
          RETURNING id, full_name, personal_id, flag",
         flag,
         personal_id
     )
-    .fetch_optional(pool)
+    .fetch_optional(&mut *tx)
     .await?;
-    
+
+    if updated_suspect.is_some() {
+        outbox::enqueue(&mut tx, personal_id, flag).await?;
+    }
+
+    tx.commit().await?;
+
     Ok(updated_suspect)
 }
 
+/// Retrieves all cases from the database
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// * `Result<Vec<Case>, sqlx::Error>` - List of all cases
+pub async fn get_all_cases(pool: &PgPool) -> Result<Vec<Case>, sqlx::Error> {
+    let cases = sqlx::query_as!(
+        Case,
+        "SELECT id, case_number, status, description, created_at FROM cases ORDER BY id"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(cases)
+}
+
+/// Retrieves a case by its database ID
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `id` - Case's database ID
+///
+/// # Returns
+///
+/// * `Result<Option<Case>, sqlx::Error>` - Case if found, None otherwise
+pub async fn get_case_by_id(pool: &PgPool, id: i32) -> Result<Option<Case>, sqlx::Error> {
+    let case = sqlx::query_as!(
+        Case,
+        "SELECT id, case_number, status, description, created_at FROM cases WHERE id = $1",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(case)
+}
+
+/// Creates a new case in the database
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `case_number` - Unique case number
+/// * `status` - Case status (`open`, `closed`, or `under_review`)
+/// * `description` - Optional free-text description
+///
+/// # Returns
+///
+/// * `Result<Case, sqlx::Error>` - Created case with generated ID
+pub async fn create_case(
+    pool: &PgPool,
+    case_number: String,
+    status: String,
+    description: Option<String>,
+) -> Result<Case, sqlx::Error> {
+    let created_case = sqlx::query_as!(
+        Case,
+        "INSERT INTO cases (case_number, status, description)
+         VALUES ($1, $2, $3)
+         RETURNING id, case_number, status, description, created_at",
+        case_number,
+        status,
+        description
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(created_case)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,10 +3,14 @@ mod database;
 mod models;
 mod middleware;
 mod utils;
+mod sync;
+mod metrics;
+mod openapi;
 
 use actix_web::{web, App, HttpServer, middleware as actix_middleware};
 use actix_cors::Cors;
 use actix_governor::{Governor, GovernorConfigBuilder};
+use sqlx::PgPool;
 use std::env;
 use std::fs::File;
 use std::io::BufReader;
@@ -17,10 +21,20 @@ use rustls_pemfile::{certs, pkcs8_private_keys};
 async fn main() -> std::io::Result<()> {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
-    
-    // Initialize logger
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
-    
+
+    // Bridge existing `log::` call sites into the `tracing` subscriber so
+    // they're still captured (and get span context, e.g. request_id)
+    // without having to rewrite every call site to `tracing::` macros.
+    tracing_log::LogTracer::init().expect("Failed to install LogTracer bridge");
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::new(
+            env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+        ))
+        .init();
+
+    // Install the Prometheus recorder before any `metrics::` macro can fire
+    let metrics_handle = metrics::init_recorder();
+
     log::info!("🚔 Police System Starting...");
     
     // Validate security configuration
@@ -37,14 +51,66 @@ async fn main() -> std::io::Result<()> {
         .parse::<bool>()
         .unwrap_or(false);
     
-    // Get API key for validating incoming requests from hospital
-    let api_key = env::var("API_KEY")
-        .expect("API_KEY must be set for shared endpoint authentication");
-    
-    if api_key.len() < 32 {
-        panic!("API_KEY must be at least 32 characters long");
-    }
-    
+    // Registered partner public keys for verifying signed requests to
+    // /api/shared/* - replaces the single shared X-API-Key, which was
+    // bearer-style and couldn't attribute a request to a specific caller.
+    let signature_keys = middleware::SignatureKeyRegistry::from_env()
+        .expect("Failed to load HTTP_SIGNATURE_PUBLIC_KEYS");
+    let signature_max_clock_skew_seconds: i64 = env::var("HTTP_SIGNATURE_MAX_CLOCK_SKEW_SECONDS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse()
+        .unwrap_or(300);
+
+    // Secret pepper keying hash_for_logging - fail fast rather than let a
+    // missing/weak pepper silently degrade personal_id pseudonymization
+    let log_hash_pepper = utils::logging::LoggingPepper::from_env()
+        .expect("Failed to load LOG_HASH_PEPPER");
+
+    // Secret signing the CSRF double-submit cookie - kept separate from the
+    // logging pepper above so rotating one never invalidates the other
+    let csrf_secret = middleware::CsrfSecret::from_env()
+        .expect("Failed to load CSRF_SECRET");
+
+    // Secret signing operator session JWTs issued by /auth/login - kept
+    // separate from the CSRF secret and logging pepper above so rotating
+    // one never invalidates the others
+    let session_secret = middleware::SessionSecret::from_env()
+        .expect("Failed to load SESSION_JWT_SECRET");
+
+    // Operator accounts allowed to log in via /auth/login
+    let operator_registry = middleware::OperatorRegistry::from_env()
+        .expect("Failed to load OPERATOR_CREDENTIALS");
+
+    // Casbin RBAC policy gating what each verified /api/shared/* caller
+    // (identified by their HTTP Signature keyId) may do - see
+    // middleware::permissions and rbac/{model.conf,policy.csv}
+    let permissions = middleware::Permissions::from_env()
+        .await
+        .expect("Failed to load RBAC_MODEL_PATH/RBAC_POLICY_PATH");
+
+    // Secret signing short-lived scoped tokens minted by POST /api/shared/token,
+    // the alternative to a Signature header for callers that would rather
+    // not hold the long-lived HOSPITAL_API_KEY on every request
+    let jwt_secret = middleware::JwtSecret::from_env()
+        .expect("Failed to load JWT_SECRET");
+    let jwt_ttl_seconds: i64 = env::var("JWT_TTL_SECONDS")
+        .unwrap_or_else(|_| "900".to_string())
+        .parse()
+        .unwrap_or(900);
+
+    // Per-caller rate limits for /api/shared/*, keyed by the verified
+    // Signature keyId or JWT subject (see middleware::rate_limit),
+    // isolating distinct integrators instead of the blanket per-IP limiter
+    // below, which many hospital callers behind one NAT would share
+    let shared_api_rate_limit_per_second: u64 = env::var("SHARED_API_RATE_LIMIT_PER_SECOND")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .unwrap_or(10);
+    let shared_api_rate_limit_burst: u32 = env::var("SHARED_API_RATE_LIMIT_BURST")
+        .unwrap_or_else(|_| "20".to_string())
+        .parse()
+        .unwrap_or(20);
+
     // Parse allowed origins from environment variable
     let allowed_origins_str = env::var("ALLOWED_ORIGINS")
         .unwrap_or_else(|_| {
@@ -63,7 +129,8 @@ async fn main() -> std::io::Result<()> {
     }
     
     log::info!("✅ Security configuration loaded");
-    log::info!("   - API Key authentication: ENABLED for shared endpoints");
+    log::info!("   - HTTP Signature authentication: ENABLED for shared endpoints (rsa-sha256)");
+    log::info!("   - RBAC authorization: ENABLED for shared endpoints (Casbin)");
     log::info!("   - TLS: {}", if enable_tls { "ENABLED" } else { "DISABLED (dev only)" });
     log::info!("   - Allowed CORS origins: {:?}", allowed_origins);
     
@@ -94,7 +161,35 @@ async fn main() -> std::io::Result<()> {
         .expect("Failed to create database connection pool");
     
     log::info!("✅ Database connection established");
-    
+
+    if database::should_skip_migrations() {
+        log::warn!("⚠️  SKIP_AUTO_MIGRATIONS is set - skipping automatic migrations (expected on read-only replicas)");
+    } else {
+        database::run_migrations(&pool)
+            .await
+            .expect("Failed to run database migrations");
+    }
+
+    // Tamper-evident audit trail - seeds its in-memory chain tip from the
+    // latest `audit_log` row (or the genesis hash if the table is empty)
+    let audit_chain = utils::audit::AuditChain::from_db(&pool)
+        .await
+        .expect("Failed to seed audit chain from database");
+
+    // Start the flag-sync outbox worker: delivers queued flag changes to the
+    // hospital system's /api/shared/sync/flag endpoint, signed with a shared
+    // secret so the receiver can verify authenticity and reject replays.
+    let hospital_sync_url = env::var("HOSPITAL_SYNC_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:8001/api/shared/sync/flag".to_string());
+    let sync_shared_secret = env::var("SYNC_SHARED_SECRET")
+        .expect("SYNC_SHARED_SECRET must be set for flag synchronization");
+
+    sync::spawn_sync_worker(pool.clone(), hospital_sync_url, sync_shared_secret);
+
+    // Publish DB pool saturation as gauges so connection exhaustion shows up
+    // in the same dashboards as request latency
+    metrics::spawn_pool_gauges(pool.clone());
+
     // Configure rate limiting
     let governor_conf = GovernorConfigBuilder::default()
         .per_second(10)  // Allow 10 requests per second
@@ -104,18 +199,36 @@ async fn main() -> std::io::Result<()> {
     
     // Log available routes
     log::info!("📋 Configuring routes:");
-    log::info!("   - GET    /suspects");
-    log::info!("   - POST   /suspects");
-    log::info!("   - GET    /suspects/{{id}}");
-    log::info!("   - PUT    /suspects/{{id}}");
-    log::info!("   - DELETE /suspects/{{id}}");
-    log::info!("   - GET    /suspects/personal/{{personal_id}}");
-    log::info!("   - PUT    /suspects/{{personal_id}}/flag");
-    log::info!("   - GET    /api/shared/suspects (Authenticated)");
-    log::info!("   - GET    /api/shared/suspects/{{personal_id}} (Authenticated)");
-    
-    log::info!("🔒 API Key authentication required for /api/shared/* endpoints");
-    log::info!("⏱️  Rate limiting: 10 req/s, burst 20");
+    log::info!("   - GET    /suspects (Authenticated)");
+    log::info!("   - POST   /suspects (Authenticated)");
+    log::info!("   - GET    /suspects/{{id}} (Authenticated)");
+    log::info!("   - PUT    /suspects/{{id}} (Authenticated)");
+    log::info!("   - DELETE /suspects/{{id}} (Authenticated)");
+    log::info!("   - GET    /suspects/personal/{{personal_id}} (Authenticated)");
+    log::info!("   - POST   /suspects/flag (Authenticated)");
+    log::info!("   - GET    /cases (Authenticated)");
+    log::info!("   - POST   /cases (Authenticated)");
+    log::info!("   - GET    /cases/{{id}} (Authenticated)");
+    log::info!("   - POST   /auth/login");
+    log::info!("   - POST   /auth/logout");
+    log::info!("   - POST   /auth/refresh");
+    log::info!("   - GET    /api-docs (Swagger UI)");
+    log::info!("   - GET    /api-docs/openapi.json");
+    log::info!("   - POST   /api/shared/token (Requires HOSPITAL_API_KEY)");
+    log::info!("   - GET    /api/shared/suspects (Signature or scoped JWT)");
+    log::info!("   - GET    /api/shared/suspects/{{personal_id}} (Signature or scoped JWT)");
+    log::info!("   - GET    /api/shared/audit/verify (Authenticated)");
+    log::info!("   - GET    /healthz (Liveness probe)");
+    log::info!("   - GET    /readyz (Readiness probe)");
+    log::info!("   - GET    /metrics (Prometheus scrape, loopback only)");
+
+    log::info!("🔒 HTTP Signature (rsa-sha256) or scoped JWT authentication required for /api/shared/* endpoints");
+    log::info!("⏱️  Rate limiting: 10 req/s, burst 20 (per IP, global)");
+    log::info!(
+        "⏱️  Rate limiting: {} req/s, burst {} (per verified caller, /api/shared/* only)",
+        shared_api_rate_limit_per_second,
+        shared_api_rate_limit_burst
+    );
     
     // Clone variables for move into closure
     let allowed_origins_clone = allowed_origins.clone();
@@ -140,6 +253,11 @@ async fn main() -> std::io::Result<()> {
         }
         
         App::new()
+            // Innermost: transparently gzip/br/zstd-encodes response bodies
+            // for callers that send `Accept-Encoding` - the shared API's
+            // paginated suspect pages are the main beneficiary, but it's
+            // harmless (and a no-op) for every other JSON response too.
+            .wrap(actix_middleware::Compress::default())
             // Add security middleware
             .wrap(actix_middleware::Logger::default())
             .wrap(cors)
@@ -152,22 +270,86 @@ async fn main() -> std::io::Result<()> {
                 .add(("X-XSS-Protection", "1; mode=block"))
                 .add(("Strict-Transport-Security", "max-age=31536000; includeSubDomains"))
             )
-            
+
+            // Signed double-submit CSRF cookie for state-changing requests
+            // to /suspects and /cases - /api/shared/* and GET requests are
+            // exempt (see should_skip_csrf_check)
+            .wrap(middleware::CsrfProtection::new(enable_tls, csrf_secret.clone()))
+
+            // Outermost: request id + tracing span, then request metrics, so
+            // both see the full request including CORS/rate-limit handling
+            .wrap(metrics::RequestMetrics)
+            .wrap(middleware::RequestTracing)
+
             // Share database pool across all handlers
             .app_data(web::Data::new(pool.clone()))
-            
+            .app_data(web::Data::new(metrics_handle.clone()))
+            .app_data(web::Data::new(log_hash_pepper.clone()))
+            .app_data(web::Data::new(session_secret.clone()))
+            .app_data(web::Data::new(operator_registry.clone()))
+            .app_data(web::Data::new(middleware::session::TlsEnabled(enable_tls)))
+            .app_data(web::Data::new(jwt_secret.clone()))
+            .app_data(web::Data::new(middleware::SharedApiJwtTtlSeconds(jwt_ttl_seconds)))
+            .app_data(web::Data::new(audit_chain.clone()))
+
             // Configure API routes
+            .configure(api::configure_auth)
             .configure(api::configure_suspects)
-            
+            .configure(api::configure_cases)
+            .configure(api::configure_audit)
+            .configure(openapi::configure_openapi)
+
             // Shared API routes with authentication
             .service(
                 web::scope("/api/shared")
-                    .wrap(middleware::ApiKeyAuth::new(api_key.clone()))
-                    .configure(api::configure_shared)
+                    // Exchanges HOSPITAL_API_KEY for a short-lived scoped
+                    // token - mounted outside the wraps below since a
+                    // caller can't present a bearer token it doesn't have yet
+                    .configure(api::configure_shared_token)
+                    .service(
+                        web::scope("")
+                            // Registered before the auth wraps below, so it
+                            // ends up as the innermost layer - it runs
+                            // after HttpSignatureAuth/JwtAuth have already
+                            // resolved and attached the caller's identity,
+                            // so distinct integrators are isolated by that
+                            // identity instead of sharing the blanket
+                            // per-IP Governor above across every hospital
+                            // caller behind the same NAT; a throttled
+                            // request also leaves an audit entry (see
+                            // middleware::rate_limit).
+                            .wrap(middleware::configure_shared_api_rate_limiter(
+                                shared_api_rate_limit_per_second,
+                                shared_api_rate_limit_burst,
+                            ))
+                            .wrap(middleware::SharedApiRateLimitAudit::new(pool.clone(), audit_chain.clone()))
+                            // Innermost of the auth wraps: only runs when no
+                            // Signature header was presented (see
+                            // middleware::jwt_auth)
+                            .wrap(middleware::JwtAuth::new(jwt_secret.clone()))
+                            // Outermost: verifies a Signature header and,
+                            // on success, RBAC-enforces it - otherwise
+                            // passes through to JwtAuth above
+                            .wrap(middleware::HttpSignatureAuth::new(
+                                signature_keys.clone(),
+                                signature_max_clock_skew_seconds,
+                                permissions.clone(),
+                                pool.clone(),
+                                audit_chain.clone(),
+                            ))
+                            .configure(api::configure_shared)
+                    )
             )
-            
+
             // Health check endpoint
             .route("/health", web::get().to(health_check))
+            // Orchestration probes: /healthz never touches the database,
+            // /readyz does, so a rolling deploy can tell "process up" apart
+            // from "can actually serve traffic"
+            .route("/healthz", web::get().to(healthz))
+            .route("/readyz", web::get().to(readyz))
+            // Prometheus scrape endpoint - guarded to loopback callers only
+            .route("/metrics", web::get().to(metrics::metrics_handler))
     });
     
     // Bind server with or without TLS
@@ -301,20 +483,21 @@ fn load_tls_config() -> std::io::Result<ServerConfig> {
 
 /// Validate that required security configuration is present
 fn validate_security_config() {
-    // Check for API key
-    let api_key = env::var("API_KEY");
-    
+    // Check for the registered partner public keys used to verify signed
+    // requests to /api/shared/*
+    let signature_keys = env::var("HTTP_SIGNATURE_PUBLIC_KEYS");
+
     if !cfg!(debug_assertions) {
-        // Production mode - API key is required
-        api_key.expect("API_KEY must be set in production");
+        // Production mode - registered signing keys are required
+        signature_keys.expect("HTTP_SIGNATURE_PUBLIC_KEYS must be set in production");
         log::info!("✅ Security configuration validated");
     } else {
-        // Debug mode - warn if API key is missing
-        if api_key.is_err() {
-            log::warn!("⚠️  Running in DEBUG mode - API_KEY not set");
-            log::warn!("⚠️  Set API_KEY for production deployment");
+        // Debug mode - warn if no signing keys are registered
+        if signature_keys.is_err() {
+            log::warn!("⚠️  Running in DEBUG mode - HTTP_SIGNATURE_PUBLIC_KEYS not set");
+            log::warn!("⚠️  Set HTTP_SIGNATURE_PUBLIC_KEYS for production deployment");
         } else {
-            log::info!("✅ API_KEY configured (debug mode)");
+            log::info!("✅ HTTP_SIGNATURE_PUBLIC_KEYS configured (debug mode)");
         }
     }
 }
@@ -325,4 +508,42 @@ async fn health_check() -> actix_web::HttpResponse {
         "status": "healthy",
         "service": "police-system"
     }))
+}
+
+/// GET /healthz - Liveness probe. Always 200 once the process is up and
+/// serving requests, independent of the database or any other downstream
+/// dependency - orchestration should only use this to decide whether to
+/// restart the container, not whether to route traffic to it (see
+/// [`readyz`] for that).
+async fn healthz() -> actix_web::HttpResponse {
+    actix_web::HttpResponse::Ok().json(serde_json::json!({ "status": "alive" }))
+}
+
+/// GET /readyz - Readiness probe. Runs a cheap `SELECT 1` against the pool
+/// and reports in-use/idle connection counts either way, so a 503 here can
+/// be told apart from an exhausted-but-reachable pool at a glance.
+async fn readyz(pool: web::Data<PgPool>) -> actix_web::HttpResponse {
+    let max_connections = pool.options().get_max_connections();
+    let idle = pool.num_idle() as u32;
+    let in_use = pool.size().saturating_sub(idle);
+    let pool_stats = serde_json::json!({
+        "max_connections": max_connections,
+        "in_use": in_use,
+        "idle": idle,
+    });
+
+    match sqlx::query("SELECT 1").execute(pool.get_ref()).await {
+        Ok(_) => actix_web::HttpResponse::Ok().json(serde_json::json!({
+            "status": "ready",
+            "pool": pool_stats,
+        })),
+        Err(e) => {
+            log::error!("Readiness check failed: {}", e);
+            actix_web::HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "status": "unavailable",
+                "error": "database unreachable",
+                "pool": pool_stats,
+            }))
+        }
+    }
 }
\ No newline at end of file
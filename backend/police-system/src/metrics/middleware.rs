@@ -0,0 +1,82 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    body::MessageBody,
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+/// Records per-route request counts, status-code breakdown and latency for
+/// every request, under the `http_requests_total` counter and
+/// `http_request_duration_seconds` histogram. Mount this above the route
+/// config so `match_pattern()` resolves to the route template (e.g.
+/// `/suspects/{id}`) rather than the literal path, keeping cardinality low.
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware { service }))
+    }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let method = req.method().to_string();
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| "unmatched".to_string());
+        let started_at = Instant::now();
+
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+            let status = res.status().as_u16().to_string();
+            let elapsed = started_at.elapsed().as_secs_f64();
+
+            metrics::counter!(
+                "http_requests_total",
+                "method" => method.clone(),
+                "route" => route.clone(),
+                "status" => status,
+            )
+            .increment(1);
+
+            metrics::histogram!(
+                "http_request_duration_seconds",
+                "method" => method,
+                "route" => route,
+            )
+            .record(elapsed);
+
+            Ok(res)
+        })
+    }
+}
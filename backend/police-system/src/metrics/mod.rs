@@ -0,0 +1,53 @@
+pub mod middleware;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use sqlx::PgPool;
+use std::time::Duration;
+
+pub use middleware::RequestMetrics;
+
+/// Installs the process-wide Prometheus recorder and returns the handle used
+/// to render `/metrics`. Must be called exactly once, before any `metrics::`
+/// macro is used.
+pub fn init_recorder() -> PrometheusHandle {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("Failed to install Prometheus metrics recorder")
+}
+
+/// Spawns a background task that periodically publishes `PgPool` saturation
+/// as gauges, so connection exhaustion shows up in the same dashboards as
+/// request latency instead of only surfacing as timeouts downstream.
+pub fn spawn_pool_gauges(pool: PgPool) {
+    tokio::spawn(async move {
+        loop {
+            metrics::gauge!("db_pool_connections_in_use").set((pool.size() as usize - pool.num_idle()) as f64);
+            metrics::gauge!("db_pool_connections_idle").set(pool.num_idle() as f64);
+            metrics::gauge!("db_pool_connections_max").set(pool.options().get_max_connections() as f64);
+            tokio::time::sleep(Duration::from_secs(15)).await;
+        }
+    });
+}
+
+/// GET /metrics - Prometheus scrape endpoint.
+///
+/// Not meant to be reachable from a browser, so it's guarded here to
+/// loopback callers only (the Prometheus scraper runs on the same host, not
+/// in a user's browser). The route sits on the same `App` as every other
+/// route, so the CORS wrap applies to it too.
+pub async fn metrics_handler(req: HttpRequest, handle: web::Data<PrometheusHandle>) -> HttpResponse {
+    let is_loopback = req
+        .peer_addr()
+        .map(|addr| addr.ip().is_loopback())
+        .unwrap_or(false);
+
+    if !is_loopback {
+        log::warn!("Rejected /metrics scrape from non-loopback address: {:?}", req.peer_addr());
+        return HttpResponse::Forbidden().finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(handle.render())
+}
@@ -0,0 +1,471 @@
+use actix_web::{
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage, HttpResponse,
+    body::{BoxBody, MessageBody},
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::Utc;
+use futures_util::future::LocalBoxFuture;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+use sqlx::PgPool;
+
+use super::permissions::{derive_object_action, Permissions};
+use crate::utils::audit::{Action, AuditChain, AuditLog, AuditResult, EventType};
+
+/// Registered partner public keys, looked up by the `keyId` a caller names
+/// in its `Signature` header.
+///
+/// Loaded once at startup from `HTTP_SIGNATURE_PUBLIC_KEYS`, a comma
+/// separated `keyId=/path/to/key.pem` list (e.g.
+/// `police-system=/etc/keys/police-system.pub.pem`). Each file holds a
+/// PEM-encoded RSA public key, either PKCS#1 or SubjectPublicKeyInfo.
+#[derive(Clone, Default)]
+pub struct SignatureKeyRegistry {
+    keys: HashMap<String, RsaPublicKey>,
+}
+
+impl SignatureKeyRegistry {
+    pub fn from_env() -> Result<Self, String> {
+        let raw = env::var("HTTP_SIGNATURE_PUBLIC_KEYS")
+            .map_err(|_| "HTTP_SIGNATURE_PUBLIC_KEYS must be set for signature verification".to_string())?;
+
+        let mut keys = HashMap::new();
+        for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (key_id, path) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid HTTP_SIGNATURE_PUBLIC_KEYS entry: '{}'", entry))?;
+
+            let pem = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read public key for '{}' at {}: {}", key_id, path, e))?;
+
+            let public_key = RsaPublicKey::from_public_key_pem(&pem)
+                .or_else(|_| RsaPublicKey::from_pkcs1_pem(&pem))
+                .map_err(|e| format!("Failed to parse public key for '{}': {}", key_id, e))?;
+
+            keys.insert(key_id.to_string(), public_key);
+        }
+
+        Ok(Self { keys })
+    }
+
+    fn get(&self, key_id: &str) -> Option<&RsaPublicKey> {
+        self.keys.get(key_id)
+    }
+}
+
+/// The identity a `HttpSignatureAuth` pass establishes for a verified
+/// request. Stored in request extensions so [`crate::utils::audit::extract_actor_from_request`]
+/// can use the partner's actual `keyId` instead of hashing a shared secret.
+#[derive(Debug, Clone)]
+pub struct SignatureActor(pub String);
+
+/// Parsed `Signature: keyId="...",algorithm="...",headers="...",signature="..."` header.
+struct SignatureHeader {
+    key_id: String,
+    algorithm: String,
+    headers: Vec<String>,
+    signature: Vec<u8>,
+}
+
+fn parse_signature_header(raw: &str) -> Option<SignatureHeader> {
+    let mut key_id = None;
+    let mut algorithm = None;
+    let mut headers = None;
+    let mut signature = None;
+
+    for part in raw.split(',') {
+        let (name, value) = part.trim().split_once('=')?;
+        let value = value.trim().trim_matches('"');
+        match name.trim() {
+            "keyId" => key_id = Some(value.to_string()),
+            "algorithm" => algorithm = Some(value.to_string()),
+            "headers" => headers = Some(value.split_whitespace().map(str::to_string).collect()),
+            "signature" => signature = BASE64.decode(value).ok(),
+            _ => {}
+        }
+    }
+
+    Some(SignatureHeader {
+        key_id: key_id?,
+        algorithm: algorithm?,
+        headers: headers?,
+        signature: signature?,
+    })
+}
+
+/// Minimum header set every `Signature` must cover. `headers=""` is
+/// entirely caller-declared, so without this floor a caller could sign just
+/// `(request-target)` - or nothing at all - turning the signature into a
+/// static bearer value that never has to cover the body or the time, and is
+/// then replayable forever against any path/method with a freshly forged
+/// `Date`/`Digest` for an attacker-chosen body.
+const REQUIRED_SIGNED_HEADERS: &[&str] = &["(request-target)", "host", "date", "digest"];
+
+/// Whether `headers` (as declared in `headers="..."`) is a superset of
+/// [`REQUIRED_SIGNED_HEADERS`], case-insensitively.
+fn covers_required_headers(headers: &[String]) -> bool {
+    REQUIRED_SIGNED_HEADERS
+        .iter()
+        .all(|required| headers.iter().any(|h| h.eq_ignore_ascii_case(required)))
+}
+
+/// Rebuilds the cavage signing string from the actual request, pulling each
+/// named header's value in the order the caller declared them. `(request-target)`
+/// is synthesized from the request's method and path rather than read off a
+/// header, matching what the signer used to produce the signature.
+fn build_signing_string(req: &ServiceRequest, header_names: &[String]) -> Option<String> {
+    let mut lines = Vec::with_capacity(header_names.len());
+    for name in header_names {
+        if name.eq_ignore_ascii_case("(request-target)") {
+            let target = match req.uri().path_and_query() {
+                Some(pq) => pq.as_str().to_string(),
+                None => req.path().to_string(),
+            };
+            lines.push(format!("(request-target): {} {}", req.method().as_str().to_lowercase(), target));
+        } else {
+            let value = req.headers().get(name.as_str())?.to_str().ok()?;
+            lines.push(format!("{}: {}", name.to_lowercase(), value));
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+fn unauthorized(reason: &str, code: &str) -> HttpResponse {
+    log::warn!("HTTP signature verification failed: {}", reason);
+    HttpResponse::Unauthorized().json(serde_json::json!({
+        "error": reason,
+        "code": code,
+    }))
+}
+
+fn forbidden(reason: &str, code: &str) -> HttpResponse {
+    log::warn!("RBAC check failed: {}", reason);
+    HttpResponse::Forbidden().json(serde_json::json!({
+        "error": reason,
+        "code": code,
+    }))
+}
+
+/// Maps the `READ`/`CREATE`/`UPDATE`/`DELETE` action [`derive_object_action`]
+/// derives into the audit log's own `Action` enum.
+fn audit_action_for(action: &str) -> Action {
+    match action {
+        "CREATE" => Action::Create,
+        "UPDATE" => Action::Update,
+        "DELETE" => Action::Delete,
+        _ => Action::Read,
+    }
+}
+
+/// Middleware verifying draft-cavage style `Signature` headers on the shared
+/// inter-system API, replacing a bearer-style `X-API-Key` with a per-partner
+/// RSA keypair the audit log can attribute a request to by name.
+///
+/// The caller signs `(request-target): <method> <path>\nhost: ...\ndate:
+/// ...\ndigest: SHA-256=<base64(sha256(body))>` (exact header set declared in
+/// `headers=""`) with RSA-SHA256 under a private key registered here by
+/// `keyId`. This middleware reconstructs that same string from the request
+/// actually received, recomputes the body digest to rule out tampering, and
+/// rejects a `Date` outside `max_clock_skew_seconds` to rule out replay.
+/// Once verified, the caller's `keyId` is also checked against the Casbin
+/// policy in [`crate::middleware::permissions`] before the request is
+/// allowed through.
+///
+/// A request with no `Signature` header at all is passed through rather
+/// than rejected here - it's assumed to be using the alternative scoped-JWT
+/// path, authenticated instead by the [`crate::middleware::jwt_auth::JwtAuth`]
+/// middleware wrapped just inside this one.
+pub struct HttpSignatureAuth {
+    keys: Arc<SignatureKeyRegistry>,
+    max_clock_skew_seconds: i64,
+    permissions: Permissions,
+    pool: PgPool,
+    chain: AuditChain,
+}
+
+impl HttpSignatureAuth {
+    pub fn new(
+        keys: SignatureKeyRegistry,
+        max_clock_skew_seconds: i64,
+        permissions: Permissions,
+        pool: PgPool,
+        chain: AuditChain,
+    ) -> Self {
+        Self {
+            keys: Arc::new(keys),
+            max_clock_skew_seconds,
+            permissions,
+            pool,
+            chain,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for HttpSignatureAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = HttpSignatureAuthMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HttpSignatureAuthMiddleware {
+            service: Rc::new(service),
+            keys: self.keys.clone(),
+            max_clock_skew_seconds: self.max_clock_skew_seconds,
+            permissions: self.permissions.clone(),
+            pool: self.pool.clone(),
+            chain: self.chain.clone(),
+        }))
+    }
+}
+
+pub struct HttpSignatureAuthMiddleware<S> {
+    service: Rc<S>,
+    keys: Arc<SignatureKeyRegistry>,
+    max_clock_skew_seconds: i64,
+    permissions: Permissions,
+    pool: PgPool,
+    chain: AuditChain,
+}
+
+impl<S, B> Service<ServiceRequest> for HttpSignatureAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let keys = self.keys.clone();
+        let max_clock_skew_seconds = self.max_clock_skew_seconds;
+        let permissions = self.permissions.clone();
+        let pool = self.pool.clone();
+        let chain = self.chain.clone();
+
+        let raw_signature_header = req.headers().get("Signature").and_then(|h| h.to_str().ok()).map(str::to_string);
+
+        // No Signature header at all - this caller is using the
+        // alternative scoped-JWT path instead; let the inner JwtAuth
+        // middleware (wrapped around configure_shared, see main.rs) decide.
+        let Some(raw_signature_header) = raw_signature_header else {
+            return Box::pin(async move { Ok(service.call(req).await?.map_into_boxed_body()) });
+        };
+
+        let Some(sig) = parse_signature_header(&raw_signature_header) else {
+            return Box::pin(async move {
+                Ok(req.into_response(unauthorized("Malformed Signature header", "SIGNATURE_MALFORMED").map_into_boxed_body()))
+            });
+        };
+
+        if !sig.algorithm.eq_ignore_ascii_case("rsa-sha256") {
+            return Box::pin(async move {
+                Ok(req.into_response(unauthorized("Unsupported signature algorithm", "SIGNATURE_ALGORITHM_UNSUPPORTED").map_into_boxed_body()))
+            });
+        }
+
+        // Reject before trusting the signature at all - otherwise a caller
+        // declaring a weak `headers=""` set would still pass every check
+        // below, since those only verify that whatever *was* signed is
+        // internally consistent with the current request.
+        if !covers_required_headers(&sig.headers) {
+            return Box::pin(async move {
+                Ok(req.into_response(
+                    unauthorized("Signature does not cover the required header set", "SIGNATURE_HEADERS_INSUFFICIENT").map_into_boxed_body(),
+                ))
+            });
+        }
+
+        let date_header = req
+            .headers()
+            .get("date")
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_string);
+
+        let body_future = req.extract::<web::Bytes>();
+
+        Box::pin(async move {
+            let body_bytes = match body_future.await {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    return Ok(req.into_response(
+                        unauthorized("Failed to read request body for digest verification", "SIGNATURE_BODY_UNREADABLE")
+                            .map_into_boxed_body(),
+                    ));
+                }
+            };
+
+            // Give the downstream handler back an intact body - this
+            // middleware only borrowed it to compute the digest.
+            req.set_payload(bytes_to_payload(body_bytes.clone()));
+
+            let Some(date) = date_header else {
+                return Ok(req.into_response(unauthorized("Missing Date header", "SIGNATURE_DATE_MISSING").map_into_boxed_body()));
+            };
+
+            let parsed_date = match chrono::DateTime::parse_from_rfc2822(&date) {
+                Ok(d) => d.with_timezone(&Utc),
+                Err(_) => {
+                    return Ok(req.into_response(unauthorized("Unparseable Date header", "SIGNATURE_DATE_INVALID").map_into_boxed_body()));
+                }
+            };
+
+            let skew = (Utc::now() - parsed_date).num_seconds().abs();
+            if skew > max_clock_skew_seconds {
+                return Ok(req.into_response(
+                    unauthorized("Date header outside the allowed clock-skew window", "SIGNATURE_DATE_EXPIRED").map_into_boxed_body(),
+                ));
+            }
+
+            let expected_digest = format!("SHA-256={}", BASE64.encode(Sha256::digest(&body_bytes)));
+            let provided_digest = req.headers().get("digest").and_then(|h| h.to_str().ok()).map(str::to_string);
+            match provided_digest {
+                Some(provided) if expected_digest.as_bytes().ct_eq(provided.as_bytes()).into() => {}
+                _ => {
+                    return Ok(req.into_response(unauthorized("Digest header missing or does not match body", "SIGNATURE_DIGEST_MISMATCH").map_into_boxed_body()));
+                }
+            }
+
+            let Some(public_key) = keys.get(&sig.key_id) else {
+                return Ok(req.into_response(unauthorized("Unknown keyId", "SIGNATURE_UNKNOWN_KEY").map_into_boxed_body()));
+            };
+
+            let Some(signing_string) = build_signing_string(&req, &sig.headers) else {
+                return Ok(req.into_response(unauthorized("Signed header missing from request", "SIGNATURE_HEADER_MISSING").map_into_boxed_body()));
+            };
+
+            let hashed = Sha256::digest(signing_string.as_bytes());
+            if public_key
+                .verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, &sig.signature)
+                .is_err()
+            {
+                return Ok(req.into_response(unauthorized("Signature verification failed", "SIGNATURE_INVALID").map_into_boxed_body()));
+            }
+
+            log::info!("HTTP signature verified for keyId '{}' on {}", sig.key_id, req.path());
+            req.extensions_mut().insert(SignatureActor(sig.key_id.clone()));
+
+            let (object, action) = derive_object_action(&req);
+            if !permissions.enforce(&sig.key_id, &object, &action).await {
+                AuditLog::new(
+                    EventType::SharedApiAccess,
+                    sig.key_id.clone(),
+                    audit_action_for(&action),
+                    object,
+                    AuditResult::Failure,
+                )
+                .with_details(format!("RBAC denied: {} not permitted to {}", sig.key_id, action))
+                .write(&chain, &pool)
+                .await;
+
+                return Ok(req.into_response(
+                    forbidden("Not authorized to perform this action", "RBAC_DENIED").map_into_boxed_body(),
+                ));
+            }
+
+            let res = service.call(req).await?;
+            Ok(res.map_into_boxed_body())
+        })
+    }
+}
+
+/// Wraps already-buffered bytes back into a `Payload` actix-web can re-read,
+/// so verifying the digest doesn't starve the handler of the request body.
+fn bytes_to_payload(buf: web::Bytes) -> Payload {
+    let (_, mut h1_payload) = actix_http::h1::Payload::create(true);
+    h1_payload.unread_data(buf);
+    Payload::from(h1_payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_parse_signature_header() {
+        let raw = r#"keyId="police-system",algorithm="rsa-sha256",headers="(request-target) host date digest",signature="c2lnbmF0dXJl""#;
+        let parsed = parse_signature_header(raw).expect("should parse");
+
+        assert_eq!(parsed.key_id, "police-system");
+        assert_eq!(parsed.algorithm, "rsa-sha256");
+        assert_eq!(parsed.headers, vec!["(request-target)", "host", "date", "digest"]);
+        assert_eq!(parsed.signature, b"signature");
+    }
+
+    #[test]
+    fn test_parse_signature_header_rejects_missing_fields() {
+        let raw = r#"keyId="police-system",algorithm="rsa-sha256""#;
+        assert!(parse_signature_header(raw).is_none());
+    }
+
+    #[test]
+    fn test_build_signing_string_includes_request_target() {
+        let req = TestRequest::get()
+            .uri("/api/shared/suspects")
+            .insert_header(("host", "police-system.local"))
+            .insert_header(("date", "Sun, 26 Jul 2026 12:00:00 GMT"))
+            .to_srv_request();
+
+        let signing_string = build_signing_string(&req, &[
+            "(request-target)".to_string(),
+            "host".to_string(),
+            "date".to_string(),
+        ])
+        .expect("should build");
+
+        assert_eq!(
+            signing_string,
+            "(request-target): get /api/shared/suspects\nhost: police-system.local\ndate: Sun, 26 Jul 2026 12:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn test_build_signing_string_missing_header_fails() {
+        let req = TestRequest::get().uri("/api/shared/suspects").to_srv_request();
+        assert!(build_signing_string(&req, &["digest".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_covers_required_headers_rejects_partial_set() {
+        assert!(!covers_required_headers(&["(request-target)".to_string()]));
+        assert!(!covers_required_headers(&[]));
+        assert!(!covers_required_headers(&[
+            "(request-target)".to_string(),
+            "host".to_string(),
+            "date".to_string(),
+        ]));
+    }
+
+    #[test]
+    fn test_covers_required_headers_accepts_superset() {
+        assert!(covers_required_headers(&[
+            "(request-target)".to_string(),
+            "host".to_string(),
+            "date".to_string(),
+            "digest".to_string(),
+            "x-extra".to_string(),
+        ]));
+    }
+}
@@ -0,0 +1,198 @@
+use std::env;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    body::{BoxBody, MessageBody},
+    Error, HttpMessage, HttpResponse,
+};
+use futures_util::future::LocalBoxFuture;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use super::http_signature::SignatureActor;
+
+/// Minimum length, in bytes, required of `JWT_SECRET`.
+const MIN_SECRET_LEN: usize = 32;
+
+/// How long a token minted by `POST /api/shared/token` is valid for, read
+/// from `JWT_TTL_SECONDS` - shared via `app_data` so the issuing handler
+/// doesn't need the raw env var threaded through its signature.
+#[derive(Clone, Copy)]
+pub struct SharedApiJwtTtlSeconds(pub i64);
+
+/// HS256 secret signing shared-API scoped tokens minted by
+/// `POST /api/shared/token` - distinct from [`crate::middleware::session::SessionSecret`]
+/// (operator logins) so rotating one never invalidates the other.
+#[derive(Clone)]
+pub struct JwtSecret(String);
+
+impl JwtSecret {
+    pub fn from_env() -> Result<Self, String> {
+        let secret = env::var("JWT_SECRET").map_err(|_| "JWT_SECRET must be set".to_string())?;
+
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(format!("JWT_SECRET must be at least {} bytes long", MIN_SECRET_LEN));
+        }
+
+        Ok(Self(secret))
+    }
+}
+
+/// Claims carried by a shared-API scoped token, minted by `POST /api/shared/token`
+/// in exchange for the long-lived `HOSPITAL_API_KEY` and presented on later
+/// calls as `Authorization: Bearer <token>` instead of re-sending that key.
+///
+/// `sub` feeds [`crate::utils::audit::extract_actor_from_request`] the same
+/// way a verified [`SignatureActor`] does; `scopes` is checked by the
+/// handler itself (e.g. `get_shared_suspect_info` requires `suspects:read`)
+/// rather than here, since which scope a route needs is route-specific.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedApiClaims {
+    pub sub: String,
+    pub scopes: Vec<String>,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+impl SharedApiClaims {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Mints a scoped token for `subject`, valid for `ttl_seconds`.
+pub fn issue_shared_api_token(
+    subject: &str,
+    scopes: Vec<String>,
+    secret: &JwtSecret,
+    ttl_seconds: i64,
+) -> Result<String, String> {
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = SharedApiClaims {
+        sub: subject.to_string(),
+        scopes,
+        iat: now,
+        exp: now + ttl_seconds as usize,
+    };
+
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.0.as_bytes()))
+        .map_err(|e| format!("failed to encode shared API token: {}", e))
+}
+
+fn decode_shared_api_token(token: &str, secret: &JwtSecret) -> Result<SharedApiClaims, String> {
+    decode::<SharedApiClaims>(
+        token,
+        &DecodingKey::from_secret(secret.0.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map(|data| data.claims)
+    .map_err(|e| format!("invalid shared API token: {}", e))
+}
+
+fn unauthorized(reason: &str, code: &str) -> HttpResponse {
+    log::warn!("Shared API JWT rejected: {}", reason);
+    HttpResponse::Unauthorized().json(serde_json::json!({
+        "error": reason,
+        "code": code,
+    }))
+}
+
+/// Middleware authenticating `/api/shared/*` callers via a scoped
+/// `Authorization: Bearer` token, as an alternative to the RSA-SHA256
+/// `Signature` header [`crate::middleware::http_signature::HttpSignatureAuth`]
+/// verifies - lets a caller mint a short-lived, least-privilege token via
+/// `POST /api/shared/token` instead of holding a long-lived credential on
+/// every call.
+///
+/// Wrapped *inside* `HttpSignatureAuth` on the `/api/shared` scope: if that
+/// outer middleware already verified a `Signature` header, it leaves a
+/// [`SignatureActor`] in request extensions and this middleware passes the
+/// request through untouched. Only when no `Signature` header was presented
+/// does it require and validate a bearer token, inserting [`SharedApiClaims`]
+/// (and a matching `SignatureActor`, so the audit log and the shared-API
+/// rate limiter name the caller the same way on both auth paths) on
+/// success. The Casbin RBAC check runs only on the `Signature` path; a
+/// bearer-token caller is limited by its token's scopes instead.
+pub struct JwtAuth {
+    secret: Arc<JwtSecret>,
+}
+
+impl JwtAuth {
+    pub fn new(secret: JwtSecret) -> Self {
+        Self { secret: Arc::new(secret) }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for JwtAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = JwtAuthMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(JwtAuthMiddleware {
+            service,
+            secret: self.secret.clone(),
+        }))
+    }
+}
+
+pub struct JwtAuthMiddleware<S> {
+    service: S,
+    secret: Arc<JwtSecret>,
+}
+
+impl<S, B> Service<ServiceRequest> for JwtAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if req.extensions().get::<SignatureActor>().is_some() {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_boxed_body()) });
+        }
+
+        let token = req
+            .headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .map(str::to_string);
+
+        let Some(token) = token else {
+            return Box::pin(async move {
+                Ok(req.into_response(unauthorized("Missing Signature or Bearer credentials", "AUTH_MISSING").map_into_boxed_body()))
+            });
+        };
+
+        match decode_shared_api_token(&token, &self.secret) {
+            Ok(claims) => {
+                log::info!("Shared API token verified for subject '{}' on {}", claims.sub, req.path());
+                req.extensions_mut().insert(SignatureActor(claims.sub.clone()));
+                req.extensions_mut().insert(claims);
+
+                let fut = self.service.call(req);
+                Box::pin(async move { Ok(fut.await?.map_into_boxed_body()) })
+            }
+            Err(e) => Box::pin(async move {
+                Ok(req.into_response(unauthorized(&e, "TOKEN_INVALID").map_into_boxed_body()))
+            }),
+        }
+    }
+}
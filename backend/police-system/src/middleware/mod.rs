@@ -1,5 +1,17 @@
 pub mod auth;
+pub mod http_signature;
+pub mod jwt_auth;
+pub mod permissions;
 pub mod rate_limit;
+pub mod request_tracing;
+pub mod csrf;
+pub mod session;
 
 pub use auth::ApiKeyAuth;
-pub use rate_limit::{configure_rate_limiter, configure_shared_api_rate_limiter};
\ No newline at end of file
+pub use http_signature::{HttpSignatureAuth, SignatureKeyRegistry};
+pub use jwt_auth::{issue_shared_api_token, JwtAuth, JwtSecret, SharedApiClaims, SharedApiJwtTtlSeconds};
+pub use permissions::Permissions;
+pub use rate_limit::{configure_rate_limiter, configure_shared_api_rate_limiter, SharedApiRateLimitAudit};
+pub use request_tracing::RequestTracing;
+pub use csrf::{CsrfProtection, CsrfSecret, CsrfTokenPolicy};
+pub use session::{AuthenticatedOperator, OperatorRegistry, SessionSecret};
\ No newline at end of file
@@ -0,0 +1,96 @@
+use actix_web::dev::ServiceRequest;
+use casbin::{CoreApi, Enforcer, MgmtApi};
+use std::env;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Casbin-backed authorization for the shared inter-system API.
+///
+/// Replaces the all-or-nothing check a correct `X-API-Key`/`Signature`
+/// header used to grant: once [`crate::middleware::http_signature::HttpSignatureAuthMiddleware`]
+/// has verified *who* is calling (the signer's `keyId`), this subsystem
+/// decides *what* they're allowed to do, via a policy model of
+/// `g(sub, role) && keyMatch(obj, p.obj) && regexMatch(act, p.act)` - a
+/// `keyId` is assigned to one or more roles (`g`), and each role is
+/// granted actions on object patterns (`p`), e.g. `hospital_system` may
+/// `READ` `suspect:*` without being granted `DELETE`.
+///
+/// Model and policy are loaded from the files named by `RBAC_MODEL_PATH`
+/// and `RBAC_POLICY_PATH`, and can be hot-reloaded via [`Permissions::reload`]
+/// without restarting the process.
+#[derive(Clone)]
+pub struct Permissions {
+    enforcer: Arc<RwLock<Enforcer>>,
+    model_path: String,
+    policy_path: String,
+}
+
+impl Permissions {
+    pub async fn from_env() -> Result<Self, String> {
+        let model_path = env::var("RBAC_MODEL_PATH")
+            .map_err(|_| "RBAC_MODEL_PATH must be set for shared API authorization".to_string())?;
+        let policy_path = env::var("RBAC_POLICY_PATH")
+            .map_err(|_| "RBAC_POLICY_PATH must be set for shared API authorization".to_string())?;
+
+        let enforcer = Enforcer::new(model_path.clone(), policy_path.clone())
+            .await
+            .map_err(|e| format!("Failed to load RBAC model/policy: {}", e))?;
+
+        Ok(Self {
+            enforcer: Arc::new(RwLock::new(enforcer)),
+            model_path,
+            policy_path,
+        })
+    }
+
+    /// Returns whether `subject` (a verified `keyId`) may perform `action`
+    /// on `object`, per the currently loaded policy.
+    pub async fn enforce(&self, subject: &str, object: &str, action: &str) -> bool {
+        let enforcer = self.enforcer.read().await;
+        enforcer
+            .enforce((subject, object, action))
+            .unwrap_or_else(|e| {
+                log::error!("RBAC enforcement error for ({}, {}, {}): {}", subject, object, action, e);
+                false
+            })
+    }
+
+    /// Re-reads `policy_path` from disk, picking up role/grant changes
+    /// without requiring a restart.
+    pub async fn reload(&self) -> Result<(), String> {
+        let mut enforcer = self.enforcer.write().await;
+        enforcer
+            .load_policy()
+            .await
+            .map_err(|e| format!("Failed to reload RBAC policy from {} (model {}): {}", self.policy_path, self.model_path, e))
+    }
+}
+
+/// Derives the `(object, action)` pair an RBAC check is made against from
+/// the request's method and path, so each route doesn't have to compute
+/// its own.
+///
+/// Parses `req.path()` directly rather than `req.match_info()`: this runs
+/// in a scope-level middleware, which sees the request *before* the
+/// scope's own router has resolved path parameters like `personal_id`.
+///
+/// `suspect:{personal_id}` names a single suspect lookup; `suspect:*` names
+/// the collection endpoints (list, or anything else this mapping doesn't
+/// recognize more specifically), matched by a policy's `keyMatch(obj, "suspect:*")`.
+pub fn derive_object_action(req: &ServiceRequest) -> (String, String) {
+    let action = match *req.method() {
+        actix_web::http::Method::GET => "READ",
+        actix_web::http::Method::POST => "CREATE",
+        actix_web::http::Method::PUT | actix_web::http::Method::PATCH => "UPDATE",
+        actix_web::http::Method::DELETE => "DELETE",
+        _ => "READ",
+    }
+    .to_string();
+
+    let object = match req.path().strip_prefix("/api/shared/suspects/") {
+        Some(personal_id) if !personal_id.is_empty() => format!("suspect:{}", personal_id),
+        _ => "suspect:*".to_string(),
+    };
+
+    (object, action)
+}
@@ -1,7 +1,21 @@
 use actix_governor::{Governor, GovernorConfigBuilder, KeyExtractor, PeerIpKeyExtractor, governor::middleware::NoOpMiddleware};
-use actix_web::{dev::ServiceRequest, http::StatusCode, HttpResponse, ResponseError};
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::StatusCode,
+    Error, HttpResponse, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
 use sha2::{Sha256, Digest};
+use sqlx::PgPool;
 use std::fmt;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+
+use crate::utils::audit::{
+    extract_actor_from_request, extract_ip_from_request, Action, AuditChain, AuditLog,
+    AuditResult, EventType,
+};
+use super::http_signature::SignatureActor;
 
 /// Standard rate limiter using IP address
 pub fn configure_rate_limiter(requests_per_second: u64, burst_size: u32) -> Governor<PeerIpKeyExtractor, NoOpMiddleware> {
@@ -46,47 +60,69 @@ impl ApiKeyError {
     }
 }
 
-/// Custom key extractor that uses API key from X-API-Key header
+/// Custom key extractor that buckets authenticated `/api/shared` traffic by
+/// the caller identity `HttpSignatureAuth`/`JwtAuth` already verified and
+/// attached to the request extensions as [`SignatureActor`] - the same
+/// identity [`extract_actor_from_request`] uses for audit entries - rather
+/// than by `X-API-Key`, which real `suspects*` traffic authenticates via a
+/// `Signature` header or a JWT bearer token and never actually sends. This
+/// way many hospital callers behind one NAT gateway don't share a single
+/// IP-keyed bucket, and a single misbehaving partner can be throttled
+/// without affecting every other one. Falls back to a hash of `X-API-Key`,
+/// then to peer IP, when no `SignatureActor` is attached; as wrapped in
+/// `main.rs`, inside both auth middlewares, every request that reaches it
+/// carries one, and `POST /api/shared/token` is mounted outside it.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct ApiKeyExtractor;
+pub struct AuthenticatedClientExtractor;
 
-impl KeyExtractor for ApiKeyExtractor {
+impl KeyExtractor for AuthenticatedClientExtractor {
     type Key = String;
     type KeyExtractionError = ApiKeyError;
 
     fn extract(&self, req: &ServiceRequest) -> Result<Self::Key, Self::KeyExtractionError> {
-        // Extract API key from header
-        let api_key = req
-            .headers()
-            .get("X-API-Key")
-            .and_then(|h| h.to_str().ok())
-            .ok_or_else(|| ApiKeyError::new("Missing X-API-Key header"))?;
-        
-        // Hash the API key for privacy in rate limiting storage
-        // This ensures the actual key isn't stored in memory
-        let mut hasher = Sha256::new();
-        hasher.update(api_key.as_bytes());
-        let hash = format!("{:x}", hasher.finalize());
-        
-        // Use first 32 characters of hash as key
-        let key = hash[..32].to_string();
-        
-        // Log with sanitized key for monitoring
-        log::debug!("Rate limit key extracted: api_key:{}", &key[..16]);
-        
-        Ok(key)
+        if let Some(actor) = req.extensions().get::<SignatureActor>() {
+            let mut hasher = Sha256::new();
+            hasher.update(actor.0.as_bytes());
+            let key = format!("{:x}", hasher.finalize())[..32].to_string();
+
+            log::debug!("Rate limit key extracted: actor:{}", &key[..16]);
+            return Ok(key);
+        }
+
+        if let Some(api_key) = req.headers().get("X-API-Key").and_then(|h| h.to_str().ok()) {
+            // Hash the API key for privacy in rate limiting storage - this
+            // ensures the actual key isn't stored in memory
+            let mut hasher = Sha256::new();
+            hasher.update(api_key.as_bytes());
+            let hash = format!("{:x}", hasher.finalize());
+
+            // Use first 32 characters of hash as key
+            let key = hash[..32].to_string();
+
+            log::debug!("Rate limit key extracted: api_key:{}", &key[..16]);
+            return Ok(key);
+        }
+
+        let ip = req
+            .peer_addr()
+            .ok_or_else(|| ApiKeyError::new("No verified identity, X-API-Key header, or peer address to rate-limit by"))?
+            .ip();
+
+        log::debug!("Rate limit key extracted: peer_ip:{} (no verified identity or X-API-Key header)", ip);
+        Ok(format!("ip:{}", ip))
     }
 }
 
-/// Rate limiter for shared API endpoints using API key
+/// Rate limiter for shared API endpoints, keyed by the verified caller
+/// identity (see [`AuthenticatedClientExtractor`])
 pub fn configure_shared_api_rate_limiter(
     requests_per_second: u64,
     burst_size: u32,
-) -> Governor<ApiKeyExtractor, NoOpMiddleware> {
+) -> Governor<AuthenticatedClientExtractor, NoOpMiddleware> {
     let governor_conf = GovernorConfigBuilder::default()
         .per_second(requests_per_second)
         .burst_size(burst_size)
-        .key_extractor(ApiKeyExtractor)
+        .key_extractor(AuthenticatedClientExtractor)
         .finish()
         .unwrap();
     
@@ -99,53 +135,146 @@ pub fn configure_shared_api_rate_limiter(
     Governor::new(&governor_conf)
 }
 
+/// Wraps [`configure_shared_api_rate_limiter`]'s `Governor` so a throttled
+/// caller also leaves an audit trail - `actix-governor` has no hook for
+/// side effects on rejection, so this sits just outside it in `main.rs` and
+/// inspects the response status after the inner `Governor` has decided.
+pub struct SharedApiRateLimitAudit {
+    pool: PgPool,
+    chain: AuditChain,
+}
+
+impl SharedApiRateLimitAudit {
+    pub fn new(pool: PgPool, chain: AuditChain) -> Self {
+        Self { pool, chain }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SharedApiRateLimitAudit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SharedApiRateLimitAuditMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SharedApiRateLimitAuditMiddleware {
+            service: Rc::new(service),
+            pool: self.pool.clone(),
+            chain: self.chain.clone(),
+        }))
+    }
+}
+
+pub struct SharedApiRateLimitAuditMiddleware<S> {
+    service: Rc<S>,
+    pool: PgPool,
+    chain: AuditChain,
+}
+
+impl<S, B> Service<ServiceRequest> for SharedApiRateLimitAuditMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let pool = self.pool.clone();
+        let chain = self.chain.clone();
+        let actor = extract_actor_from_request(&req);
+        let ip = extract_ip_from_request(&req);
+        let resource = req.path().to_string();
+
+        Box::pin(async move {
+            let res = service.call(req).await?;
+
+            if res.status() == StatusCode::TOO_MANY_REQUESTS {
+                AuditLog::new(EventType::SharedApiAccess, actor, Action::Read, resource, AuditResult::Failure)
+                    .with_ip(ip)
+                    .with_details("rate limited".to_string())
+                    .write(&chain, &pool)
+                    .await;
+            }
+
+            Ok(res)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use actix_web::test::TestRequest;
+    use actix_web::HttpMessage;
 
     #[test]
-    fn test_api_key_extractor_success() {
-        let extractor = ApiKeyExtractor;
+    fn test_authenticated_client_extractor_prefers_signature_actor() {
+        let extractor = AuthenticatedClientExtractor;
         let req = TestRequest::default()
             .insert_header(("X-API-Key", "test_key_12345"))
             .to_srv_request();
-        
+        req.extensions_mut().insert(SignatureActor("hospital-system".to_string()));
+
         let result = extractor.extract(&req);
         assert!(result.is_ok());
-        
+
         let key = result.unwrap();
-        // Should be a hash, not the original key
+        // Should be a hash, not the original identity or API key
+        assert_ne!(key, "hospital-system");
         assert_ne!(key, "test_key_12345");
         assert_eq!(key.len(), 32);
     }
 
     #[test]
-    fn test_api_key_extractor_missing_header() {
-        let extractor = ApiKeyExtractor;
+    fn test_authenticated_client_extractor_falls_back_to_api_key() {
+        let extractor = AuthenticatedClientExtractor;
+        let req = TestRequest::default()
+            .insert_header(("X-API-Key", "test_key_12345"))
+            .to_srv_request();
+
+        let result = extractor.extract(&req);
+        assert!(result.is_ok());
+
+        let key = result.unwrap();
+        assert_ne!(key, "test_key_12345");
+        assert_eq!(key.len(), 32);
+    }
+
+    #[test]
+    fn test_authenticated_client_extractor_missing_everything() {
+        let extractor = AuthenticatedClientExtractor;
         let req = TestRequest::default().to_srv_request();
-        
+
         let result = extractor.extract(&req);
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_api_key_extractor_consistency() {
-        let extractor = ApiKeyExtractor;
-        let api_key = "consistent_key_test";
-        
-        let req1 = TestRequest::default()
-            .insert_header(("X-API-Key", api_key))
-            .to_srv_request();
-        
-        let req2 = TestRequest::default()
-            .insert_header(("X-API-Key", api_key))
-            .to_srv_request();
-        
+    fn test_authenticated_client_extractor_consistency() {
+        let extractor = AuthenticatedClientExtractor;
+
+        let req1 = TestRequest::default().to_srv_request();
+        req1.extensions_mut().insert(SignatureActor("hospital-system".to_string()));
+
+        let req2 = TestRequest::default().to_srv_request();
+        req2.extensions_mut().insert(SignatureActor("hospital-system".to_string()));
+
         let key1 = extractor.extract(&req1).unwrap();
         let key2 = extractor.extract(&req2).unwrap();
-        
-        // Same API key should produce same hash
+
+        // Same identity should produce the same bucket
         assert_eq!(key1, key2);
     }
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    body::MessageBody,
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Generates a request id for every inbound request and opens a `tracing`
+/// span around it, so a request's handler logs and DB query logs (bridged
+/// from `log::` via `tracing-log`) can be correlated by grepping one id
+/// instead of stitching timestamps back together.
+///
+/// The id is also echoed back as `X-Request-Id` so it can be matched against
+/// a client-side error report.
+pub struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestTracingMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTracingMiddleware { service }))
+    }
+}
+
+pub struct RequestTracingMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = Uuid::new_v4().to_string();
+        let span = tracing::info_span!(
+            "http_request",
+            request_id = %request_id,
+            method = %req.method(),
+            path = %req.path(),
+        );
+
+        req.extensions_mut().insert(RequestId(request_id.clone()));
+        let fut = self.service.call(req);
+
+        Box::pin(
+            async move {
+                let mut res = fut.await?;
+                res.headers_mut().insert(
+                    actix_web::http::header::HeaderName::from_static("x-request-id"),
+                    actix_web::http::header::HeaderValue::from_str(&request_id)
+                        .unwrap_or_else(|_| actix_web::http::header::HeaderValue::from_static("invalid")),
+                );
+                Ok(res)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// The per-request id generated by [`RequestTracing`], stored in the
+/// request's extensions so a handler can pull it out if it needs to log or
+/// echo it explicitly.
+#[derive(Clone)]
+pub struct RequestId(pub String);
@@ -0,0 +1,288 @@
+use std::env;
+use std::future::{ready, Ready};
+
+use actix_web::{
+    cookie::{time::Duration as CookieDuration, Cookie, SameSite},
+    dev::Payload,
+    error::InternalError,
+    web, FromRequest, HttpRequest, HttpResponse,
+};
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+/// Minimum length, in bytes, required of `SESSION_JWT_SECRET`.
+const MIN_SECRET_LEN: usize = 32;
+
+/// How long an access token cookie is valid before `/auth/refresh` is needed.
+pub const ACCESS_TTL_SECS: i64 = 900;
+/// How long the refresh token cookie is valid before the operator must log
+/// in again.
+pub const REFRESH_TTL_SECS: i64 = 7 * 24 * 3600;
+
+pub const ACCESS_COOKIE_NAME: &str = "token";
+pub const REFRESH_COOKIE_NAME: &str = "refresh_token";
+
+/// Whether session cookies should be marked `Secure` - shared via
+/// `app_data` so handlers don't need the raw `ENABLE_TLS` config flag
+/// threaded through their signatures.
+#[derive(Clone, Copy)]
+pub struct TlsEnabled(pub bool);
+
+/// HS256 secret signing operator session tokens - distinct from
+/// [`crate::middleware::csrf::CsrfSecret`] and the `hash_for_logging`
+/// pepper, so rotating one never invalidates the others.
+#[derive(Clone)]
+pub struct SessionSecret(String);
+
+impl SessionSecret {
+    pub fn from_env() -> Result<Self, String> {
+        let secret = env::var("SESSION_JWT_SECRET")
+            .map_err(|_| "SESSION_JWT_SECRET must be set".to_string())?;
+
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(format!(
+                "SESSION_JWT_SECRET must be at least {} bytes long",
+                MIN_SECRET_LEN
+            ));
+        }
+
+        Ok(Self(secret))
+    }
+}
+
+/// Which kind of session token a [`SessionClaims`] was minted as - checked
+/// on use so a refresh token can never be presented as an access token (or
+/// vice versa), even though both are HS256 JWTs signed with the same
+/// [`SessionSecret`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    Access,
+    Refresh,
+}
+
+/// Claims carried by an operator session JWT, minted by `POST /auth/login`
+/// and renewed by `POST /auth/refresh`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionClaims {
+    pub sub: String,
+    pub role: String,
+    pub iat: usize,
+    pub exp: usize,
+    pub kind: TokenKind,
+}
+
+fn encode_claims(claims: &SessionClaims, secret: &SessionSecret) -> Result<String, String> {
+    encode(&Header::default(), claims, &EncodingKey::from_secret(secret.0.as_bytes()))
+        .map_err(|e| format!("failed to encode session token: {}", e))
+}
+
+fn decode_claims(token: &str, secret: &SessionSecret) -> Result<SessionClaims, String> {
+    decode::<SessionClaims>(
+        token,
+        &DecodingKey::from_secret(secret.0.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map(|data| data.claims)
+    .map_err(|e| format!("invalid session token: {}", e))
+}
+
+/// Mints a fresh access/refresh token pair for `username`/`role`.
+pub fn issue_session_tokens(
+    username: &str,
+    role: &str,
+    secret: &SessionSecret,
+) -> Result<(String, String), String> {
+    let now = chrono::Utc::now().timestamp() as usize;
+
+    let access = SessionClaims {
+        sub: username.to_string(),
+        role: role.to_string(),
+        iat: now,
+        exp: now + ACCESS_TTL_SECS as usize,
+        kind: TokenKind::Access,
+    };
+    let refresh = SessionClaims {
+        sub: username.to_string(),
+        role: role.to_string(),
+        iat: now,
+        exp: now + REFRESH_TTL_SECS as usize,
+        kind: TokenKind::Refresh,
+    };
+
+    Ok((encode_claims(&access, secret)?, encode_claims(&refresh, secret)?))
+}
+
+/// Builds the `token`/`refresh_token` cookies for `issue_session_tokens`'s
+/// output - `http_only` always, `Secure` only when TLS is enabled.
+pub fn session_cookies(access_token: String, refresh_token: String, enable_tls: bool) -> (Cookie<'static>, Cookie<'static>) {
+    let build = |name: &'static str, value: String, max_age_secs: i64| {
+        let mut cookie = Cookie::new(name, value);
+        cookie.set_http_only(true);
+        cookie.set_same_site(SameSite::Lax);
+        cookie.set_path("/");
+        cookie.set_max_age(Some(CookieDuration::seconds(max_age_secs)));
+        if enable_tls {
+            cookie.set_secure(true);
+        }
+        cookie
+    };
+
+    (
+        build(ACCESS_COOKIE_NAME, access_token, ACCESS_TTL_SECS),
+        build(REFRESH_COOKIE_NAME, refresh_token, REFRESH_TTL_SECS),
+    )
+}
+
+/// Cookies that immediately expire the session on `POST /auth/logout`.
+pub fn logout_cookies(enable_tls: bool) -> (Cookie<'static>, Cookie<'static>) {
+    let build = |name: &'static str| {
+        let mut cookie = Cookie::new(name, "");
+        cookie.set_http_only(true);
+        cookie.set_same_site(SameSite::Lax);
+        cookie.set_path("/");
+        cookie.set_max_age(Some(CookieDuration::ZERO));
+        if enable_tls {
+            cookie.set_secure(true);
+        }
+        cookie
+    };
+
+    (build(ACCESS_COOKIE_NAME), build(REFRESH_COOKIE_NAME))
+}
+
+/// A known operator account, parsed from `OPERATOR_CREDENTIALS`.
+#[derive(Clone)]
+struct OperatorCredential {
+    username: String,
+    password_hash: String,
+    role: String,
+}
+
+/// Operator accounts allowed to log in, loaded once at startup from
+/// `OPERATOR_CREDENTIALS=username:argon2_phc_hash:role[,username2:...]` -
+/// a comma separated list in the same spirit as
+/// [`crate::middleware::http_signature::SignatureKeyRegistry`]'s
+/// `HTTP_SIGNATURE_PUBLIC_KEYS`.
+#[derive(Clone)]
+pub struct OperatorRegistry {
+    credentials: Vec<OperatorCredential>,
+}
+
+impl OperatorRegistry {
+    pub fn from_env() -> Result<Self, String> {
+        let raw = env::var("OPERATOR_CREDENTIALS")
+            .map_err(|_| "OPERATOR_CREDENTIALS must be set".to_string())?;
+
+        let mut credentials = Vec::new();
+        for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let parts: Vec<&str> = entry.splitn(3, ':').collect();
+            let [username, password_hash, role] = parts.as_slice() else {
+                return Err(format!("malformed OPERATOR_CREDENTIALS entry: '{}'", entry));
+            };
+            credentials.push(OperatorCredential {
+                username: username.to_string(),
+                password_hash: password_hash.to_string(),
+                role: role.to_string(),
+            });
+        }
+
+        if credentials.is_empty() {
+            return Err("OPERATOR_CREDENTIALS must contain at least one account".to_string());
+        }
+
+        Ok(Self { credentials })
+    }
+
+    /// Verifies `username`/`password` against the registry, returning the
+    /// account's role on success.
+    pub fn authenticate(&self, username: &str, password: &str) -> Option<String> {
+        let account = self.credentials.iter().find(|c| c.username == username)?;
+
+        let parsed_hash = PasswordHash::new(&account.password_hash).ok()?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .ok()?;
+
+        Some(account.role.clone())
+    }
+
+    /// Looks up an account's current role by username, without verifying a
+    /// password - used by `/auth/refresh` so a rotated session picks up any
+    /// role change made since login instead of trusting the old token.
+    pub fn role_for(&self, username: &str) -> Option<String> {
+        self.credentials
+            .iter()
+            .find(|c| c.username == username)
+            .map(|c| c.role.clone())
+    }
+}
+
+/// Extractor that authenticates an operator session: reads the `token`
+/// cookie set by `/auth/login`, falling back to an `Authorization: Bearer`
+/// header (used by programmatic callers that can't send cookies), and
+/// rejects with 401 if missing, invalid, expired, or not an access token.
+pub struct AuthenticatedOperator(pub SessionClaims);
+
+impl FromRequest for AuthenticatedOperator {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let secret = match req.app_data::<web::Data<SessionSecret>>() {
+            Some(secret) => secret,
+            None => return ready(Err(unauthorized_error("Session authentication not configured"))),
+        };
+
+        let token = req
+            .cookie(ACCESS_COOKIE_NAME)
+            .map(|c| c.value().to_string())
+            .or_else(|| {
+                req.headers()
+                    .get("Authorization")
+                    .and_then(|h| h.to_str().ok())
+                    .and_then(|h| h.strip_prefix("Bearer "))
+                    .map(|s| s.to_string())
+            });
+
+        let token = match token {
+            Some(token) => token,
+            None => return ready(Err(unauthorized_error("Missing session token"))),
+        };
+
+        match decode_claims(&token, secret) {
+            Ok(claims) if claims.kind == TokenKind::Access => {
+                ready(Ok(AuthenticatedOperator(claims)))
+            }
+            Ok(_) => {
+                log::warn!("Rejected session token: refresh token used where an access token was required");
+                ready(Err(unauthorized_error("Invalid or expired session")))
+            }
+            Err(e) => {
+                log::warn!("Rejected session token: {}", e);
+                ready(Err(unauthorized_error("Invalid or expired session")))
+            }
+        }
+    }
+}
+
+/// Decodes and validates a refresh token, requiring `kind == Refresh` -
+/// used directly by the `/auth/refresh` handler rather than as a
+/// `FromRequest` extractor, since refresh tokens are only ever read from
+/// the dedicated `refresh_token` cookie.
+pub fn decode_refresh_token(token: &str, secret: &SessionSecret) -> Result<SessionClaims, String> {
+    let claims = decode_claims(token, secret)?;
+    if claims.kind != TokenKind::Refresh {
+        return Err("not a refresh token".to_string());
+    }
+    Ok(claims)
+}
+
+fn unauthorized_error(message: &str) -> actix_web::Error {
+    InternalError::from_response(
+        message.to_string(),
+        HttpResponse::Unauthorized().json(serde_json::json!({ "error": message })),
+    )
+    .into()
+}
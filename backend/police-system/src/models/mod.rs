@@ -0,0 +1,5 @@
+pub mod suspect;
+pub mod case;
+
+pub use suspect::{Suspect, CreateSuspect, UpdateSuspect};
+pub use case::Case;
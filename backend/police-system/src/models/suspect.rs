@@ -1,12 +1,58 @@
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use chrono::NaiveDateTime;
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+lazy_static! {
+    // Swedish personal ID pattern: YYYYMMDD-XXXX
+    static ref PERSONAL_ID_REGEX: Regex = Regex::new(r"^\d{8}-\d{4}$").unwrap();
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Suspect {
     pub id: i32,
-    pub case_id: i32,
-    pub name: String,
+    pub full_name: String,
+    pub personal_id: Option<String>,
+    pub flag: bool,
+}
+
+impl Suspect {
+    /// Checks a Swedish personal ID against the `YYYYMMDD-XXXX` format.
+    ///
+    /// Kept as a standalone helper (rather than only living behind
+    /// `Validate`) because a couple of handlers validate a bare
+    /// `personal_id` path/query parameter that never gets deserialized
+    /// into `CreateSuspect`/`UpdateSuspect`.
+    pub fn validate_personal_id(personal_id: &str) -> bool {
+        PERSONAL_ID_REGEX.is_match(personal_id)
+    }
+}
+
+/// Rejects names that are empty once leading/trailing whitespace is
+/// stripped, so `"   "` can't sneak past the `length(min = 1)` check.
+fn non_blank(full_name: &str) -> Result<(), validator::ValidationError> {
+    if full_name.trim().is_empty() {
+        return Err(validator::ValidationError::new("blank_full_name"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateSuspect {
+    #[validate(length(min = 1, max = 255), custom = "non_blank")]
+    pub full_name: String,
+    #[validate(regex = "PERSONAL_ID_REGEX")]
+    pub personal_id: String,
+    pub flag: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateSuspect {
+    #[validate(length(min = 1, max = 255), custom = "non_blank")]
+    pub full_name: Option<String>,
+    #[validate(regex = "PERSONAL_ID_REGEX")]
     pub personal_id: Option<String>,
-    pub created_at: NaiveDateTime,
-}
\ No newline at end of file
+    pub flag: Option<bool>,
+}
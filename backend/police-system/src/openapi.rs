@@ -0,0 +1,127 @@
+use serde::Serialize;
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi, ToSchema,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api::cases::{create_case, get_all_cases, get_case_by_id, CreateCaseRequest};
+use crate::api::shared::{
+    get_all_shared_suspects, get_shared_suspect_info, issue_token, SuspectPage, TokenRequest,
+    TokenResponse,
+};
+use crate::api::suspects::{
+    create_suspect, delete_suspect, get_all_suspects, get_suspect_by_id,
+    get_suspect_by_personal_id, update_flag, update_suspect, FlagUpdateRequest,
+};
+use crate::models::{Case, CreateSuspect, Suspect, UpdateSuspect};
+
+/// The JSON body every [`crate::utils::ApiError`] variant and the CSRF
+/// middleware's rejections share: `{"error": "...", "code": "..."}`.
+/// Exists only to give utoipa something to reference from `responses(...)`
+/// - it's never constructed at runtime, since the real error envelopes are
+/// built by `ApiError::error_response` and `middleware::csrf` directly.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+/// Registers the security schemes referenced by this crate's
+/// `#[utoipa::path(security(...))]` annotations:
+///
+/// - `operator_session` - the `token` cookie issued by `POST /auth/login`
+///   (see [`crate::middleware::session`]), required on every `/suspects`
+///   and `/cases` route.
+/// - `csrf_token` - the `X-CSRF-Token` double-submit header required
+///   alongside `operator_session` on state-changing requests (see
+///   [`crate::middleware::csrf`]).
+/// - `shared_api_auth` - the RSA-SHA256 `Signature` header that guards
+///   `/api/shared/*` (see [`crate::middleware::http_signature`]); named
+///   for what currently protects those routes rather than the plain
+///   `X-API-Key` it replaced.
+/// - `hospital_api_key` - the `X-API-Key` header `POST /api/shared/token`
+///   itself is gated by (see [`crate::middleware::auth::verify_api_key`]) -
+///   the one caller on `/api/shared/*` that can't yet hold a `Signature`
+///   key or scoped JWT, since it's how it gets the latter.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi
+            .components
+            .get_or_insert_with(utoipa::openapi::Components::new);
+
+        components.add_security_scheme(
+            "operator_session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("token"))),
+        );
+        components.add_security_scheme(
+            "csrf_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-csrf-token"))),
+        );
+        components.add_security_scheme(
+            "shared_api_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "RSA-SHA256 HTTP Signature in the `Signature` header - see HTTP_SIGNATURE_PUBLIC_KEYS",
+                    ))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "hospital_api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-api-key"))),
+        );
+    }
+}
+
+/// The police system's OpenAPI document: every route under `/suspects` and
+/// `/cases`, plus the inter-system `/api/shared/*` surface (the
+/// operator-only `GET /api/shared/audit/verify` is not described), their
+/// request/response schemas, and the cookie/header/signature/JWT schemes
+/// they're secured with. Served as JSON at `GET /api-docs/openapi.json`,
+/// with an interactive Swagger UI at `GET /api-docs` for manual exploration.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_suspects,
+        get_suspect_by_id,
+        get_suspect_by_personal_id,
+        create_suspect,
+        update_suspect,
+        delete_suspect,
+        update_flag,
+        get_all_cases,
+        get_case_by_id,
+        create_case,
+        issue_token,
+        get_shared_suspect_info,
+        get_all_shared_suspects,
+    ),
+    components(schemas(
+        Suspect, CreateSuspect, UpdateSuspect, FlagUpdateRequest,
+        Case, CreateCaseRequest,
+        TokenRequest, TokenResponse, SuspectPage,
+        ErrorResponse,
+    )),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "suspects", description = "Suspect record management"),
+        (name = "cases", description = "Case record management"),
+        (name = "shared", description = "Inter-system API consumed by the hospital system"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Mounts the generated OpenAPI document and an interactive Swagger UI.
+///
+/// Unauthenticated - the spec describes the API's shape, not its data, and
+/// integrators need it before they hold an operator session.
+pub fn configure_openapi(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(
+        SwaggerUi::new("/api-docs/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi()),
+    );
+}
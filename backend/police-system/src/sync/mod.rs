@@ -0,0 +1,5 @@
+pub mod outbox;
+pub mod signature;
+pub mod worker;
+
+pub use worker::spawn_sync_worker;
@@ -0,0 +1,57 @@
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+
+/// A single queued flag-change event awaiting delivery to the hospital
+/// system's `/api/shared/sync/flag` endpoint.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub personal_id: String,
+    pub flag: bool,
+    pub sequence: i64,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Writes a flag-change event to the outbox, in the same transaction as the
+/// `suspects.flag` update, so a dropped HTTP call to the hospital system can
+/// never silently desync the two databases - the worker retries from here.
+pub async fn enqueue(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    personal_id: &str,
+    flag: bool,
+) -> Result<OutboxEvent, sqlx::Error> {
+    sqlx::query_as!(
+        OutboxEvent,
+        r#"INSERT INTO sync_outbox (personal_id, flag)
+           VALUES ($1, $2)
+           RETURNING id, personal_id, flag, sequence, created_at, delivered_at"#,
+        personal_id,
+        flag
+    )
+    .fetch_one(&mut *tx)
+    .await
+}
+
+/// Fetches up to `limit` undelivered events, oldest first.
+pub async fn fetch_pending(pool: &PgPool, limit: i64) -> Result<Vec<OutboxEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        OutboxEvent,
+        "SELECT id, personal_id, flag, sequence, created_at, delivered_at
+         FROM sync_outbox
+         WHERE delivered_at IS NULL
+         ORDER BY sequence
+         LIMIT $1",
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Marks an event as delivered after the peer acknowledges it with a 2xx.
+pub async fn mark_delivered(pool: &PgPool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!("UPDATE sync_outbox SET delivered_at = now() WHERE id = $1", id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use chrono::{DateTime, Utc};
+use subtle::ConstantTimeEq;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How far a signed payload's timestamp may drift from "now" before it's
+/// rejected as a possible replay.
+pub const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// Computes `HMAC-SHA256(shared_secret, canonical_json || sequence || timestamp)`
+/// over an outbound sync payload, returned as lowercase hex.
+pub fn sign(shared_secret: &str, canonical_json: &str, sequence: i64, timestamp: DateTime<Utc>) -> String {
+    let mut mac = HmacSha256::new_from_slice(shared_secret.as_bytes())
+        .expect("HMAC accepts a key of any length");
+    mac.update(canonical_json.as_bytes());
+    mac.update(sequence.to_string().as_bytes());
+    mac.update(timestamp.timestamp().to_string().as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Verifies a signed payload: recomputes the HMAC and rejects on mismatch or
+/// on a timestamp outside the ±5 minute window, to block replay of captured
+/// requests.
+pub fn verify(
+    shared_secret: &str,
+    canonical_json: &str,
+    sequence: i64,
+    timestamp: DateTime<Utc>,
+    signature_hex: &str,
+) -> bool {
+    let skew = (Utc::now() - timestamp).num_seconds().abs();
+    if skew > MAX_CLOCK_SKEW_SECONDS {
+        log::warn!("Sync signature rejected: timestamp skew of {}s exceeds the allowed window", skew);
+        return false;
+    }
+
+    let expected = sign(shared_secret, canonical_json, sequence, timestamp);
+
+    match hex::decode(signature_hex) {
+        Ok(provided_bytes) => {
+            let expected_bytes = match hex::decode(&expected) {
+                Ok(b) => b,
+                Err(_) => return false,
+            };
+            expected_bytes.ct_eq(&provided_bytes).into()
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let secret = "shared-secret-value";
+        let payload = r#"{"personal_id":"19900101-1234","flag":true}"#;
+        let now = Utc::now();
+
+        let signature = sign(secret, payload, 42, now);
+        assert!(verify(secret, payload, 42, now, &signature));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_payload() {
+        let secret = "shared-secret-value";
+        let now = Utc::now();
+        let signature = sign(secret, r#"{"flag":true}"#, 1, now);
+
+        assert!(!verify(secret, r#"{"flag":false}"#, 1, now, &signature));
+    }
+
+    #[test]
+    fn test_verify_rejects_stale_timestamp() {
+        let secret = "shared-secret-value";
+        let payload = r#"{"flag":true}"#;
+        let stale = Utc::now() - chrono::Duration::seconds(MAX_CLOCK_SKEW_SECONDS + 60);
+
+        let signature = sign(secret, payload, 1, stale);
+        assert!(!verify(secret, payload, 1, stale, &signature));
+    }
+}
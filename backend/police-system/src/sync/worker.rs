@@ -0,0 +1,92 @@
+use std::time::Duration;
+use sqlx::PgPool;
+use chrono::Utc;
+
+use super::{outbox, signature};
+
+const BATCH_SIZE: i64 = 20;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spawns the background worker that drains the `sync_outbox` table,
+/// delivering each pending flag-change event to the hospital system's
+/// `/api/shared/sync/flag` endpoint. Delivered events are marked so retries
+/// (ours, or the receiver's own dedup) never double-apply a flag change.
+pub fn spawn_sync_worker(pool: PgPool, peer_sync_url: String, shared_secret: String) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+
+        loop {
+            match outbox::fetch_pending(&pool, BATCH_SIZE).await {
+                Ok(events) if events.is_empty() => {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                Ok(events) => {
+                    for event in events {
+                        deliver_with_retry(&client, &pool, &peer_sync_url, &shared_secret, &event).await;
+                    }
+                }
+                Err(e) => {
+                    log::error!("Sync worker: failed to read sync_outbox: {}", e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+}
+
+async fn deliver_with_retry(
+    client: &reqwest::Client,
+    pool: &PgPool,
+    peer_sync_url: &str,
+    shared_secret: &str,
+    event: &outbox::OutboxEvent,
+) {
+    let body = serde_json::json!({
+        "personal_id": event.personal_id,
+        "flag": event.flag,
+        "sequence": event.sequence,
+    });
+    let canonical_json = body.to_string();
+
+    let mut delay = BASE_RETRY_DELAY;
+
+    loop {
+        let timestamp = Utc::now();
+        let sig = signature::sign(shared_secret, &canonical_json, event.sequence, timestamp);
+
+        let result = client
+            .post(peer_sync_url)
+            .header("X-Signature", sig)
+            .header("X-Timestamp", timestamp.timestamp().to_string())
+            .header("X-Sync-Source", "police-system")
+            .json(&body)
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                if let Err(e) = outbox::mark_delivered(pool, event.id).await {
+                    log::error!("Sync worker: delivered sequence {} but failed to mark it delivered: {}", event.sequence, e);
+                }
+                return;
+            }
+            Ok(response) => {
+                log::warn!(
+                    "Sync worker: peer rejected sequence {} with status {}, retrying in {:?}",
+                    event.sequence, response.status(), delay
+                );
+            }
+            Err(e) => {
+                log::warn!(
+                    "Sync worker: delivery of sequence {} failed ({}), retrying in {:?}",
+                    event.sequence, e, delay
+                );
+            }
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,107 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use std::fmt;
+
+use super::error_handler::generate_correlation_id;
+
+/// Crate-wide error type for handlers that talk to the database directly,
+/// implementing [`ResponseError`] so a handler can simply return
+/// `Result<HttpResponse, ApiError>` and let actix build the response.
+///
+/// The `error`/`code` JSON shape matches the Forbidden responses already
+/// returned by [`crate::middleware::csrf`] so API consumers see one
+/// consistent error envelope regardless of which layer rejected the
+/// request.
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound { resource: &'static str },
+    Conflict { code: &'static str, message: &'static str },
+    Unauthorized { code: &'static str, message: &'static str },
+    Internal { correlation_id: String },
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound { resource } => write!(f, "{} not found", resource),
+            ApiError::Conflict { message, .. } => write!(f, "{}", message),
+            ApiError::Unauthorized { message, .. } => write!(f, "{}", message),
+            ApiError::Internal { correlation_id } => {
+                write!(f, "internal error [{}]", correlation_id)
+            }
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound { .. } => StatusCode::NOT_FOUND,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            ApiError::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            ApiError::NotFound { resource } => HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("{} not found", resource),
+                "code": "NOT_FOUND",
+            })),
+            ApiError::Conflict { code, message } => {
+                HttpResponse::Conflict().json(serde_json::json!({
+                    "error": message,
+                    "code": code,
+                }))
+            }
+            ApiError::Unauthorized { code, message } => {
+                HttpResponse::Unauthorized().json(serde_json::json!({
+                    "error": message,
+                    "code": code,
+                }))
+            }
+            ApiError::Internal { correlation_id } => {
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Service temporarily unavailable",
+                    "code": "INTERNAL_ERROR",
+                    "correlation_id": correlation_id,
+                }))
+            }
+        }
+    }
+}
+
+/// Classifies a `sqlx::Error` from a `cases` or `suspects` query into an
+/// [`ApiError`]: a missing row becomes 404, a unique-constraint violation on
+/// a known `personal_id`/`case_number` column becomes 409 with a stable
+/// machine-readable code, and everything else is logged server-side and
+/// reported as a generic 500 (the same triage `handle_database_error`
+/// already did, just centralized into a type handlers can return directly).
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        if let sqlx::Error::Database(db_err) = &error {
+            if db_err.is_unique_violation() {
+                let code = match db_err.constraint() {
+                    Some("suspects_personal_id_key") => "SUSPECT_EXISTS",
+                    Some("cases_case_number_key") => "CASE_NUMBER_EXISTS",
+                    _ => "CONFLICT",
+                };
+                let message = match code {
+                    "SUSPECT_EXISTS" => "A suspect with that personal ID already exists",
+                    "CASE_NUMBER_EXISTS" => "A case with that case number already exists",
+                    _ => "A record with that value already exists",
+                };
+                log::warn!("Unique constraint violation: {}", error);
+                return ApiError::Conflict { code, message };
+            }
+        }
+
+        if matches!(error, sqlx::Error::RowNotFound) {
+            return ApiError::NotFound { resource: "resource" };
+        }
+
+        let correlation_id = generate_correlation_id();
+        log::error!("Database error [{}]: {}", correlation_id, error);
+        ApiError::Internal { correlation_id }
+    }
+}
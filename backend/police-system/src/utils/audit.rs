@@ -1,7 +1,12 @@
 use serde::Serialize;
 use chrono::{DateTime, Utc};
 use actix_web::dev::ServiceRequest;
+use actix_web::HttpMessage;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
 use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 /// Audit event types for different operations
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +18,7 @@ pub enum EventType {
     SuspectUpdate,
     SuspectDelete,
     SharedApiAccess,
+    Authentication,
 }
 
 /// Audit action types
@@ -48,6 +54,83 @@ pub struct AuditLog {
     details: Option<String>,
 }
 
+/// The hash chain's starting point, before any row has been written -
+/// 64 `0` characters so `prev_hash` is always a well-formed SHA-256 hex
+/// digest, even for the very first row.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Tracks the hash chain's tip so each new [`AuditLog::write`] call knows
+/// what `prev_hash` to extend, without re-querying the table on every
+/// write.
+///
+/// Wrapped in a `tokio::sync::Mutex` rather than a plain `std::sync::Mutex`
+/// since it's held across the `INSERT` - an `.await` point - while writing
+/// a row.
+#[derive(Clone)]
+pub struct AuditChain {
+    tip: Arc<Mutex<String>>,
+}
+
+impl AuditChain {
+    /// Seeds the chain from the table's latest row, or [`GENESIS_HASH`] if
+    /// `audit_log` is empty - called once at startup.
+    pub async fn from_db(pool: &PgPool) -> Result<Self, sqlx::Error> {
+        let tip = sqlx::query_scalar!(
+            "SELECT entry_hash FROM audit_log ORDER BY id DESC LIMIT 1"
+        )
+        .fetch_optional(pool)
+        .await?
+        .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+        Ok(Self { tip: Arc::new(Mutex::new(tip)) })
+    }
+}
+
+/// The exact byte representation hashed into the chain - built from each
+/// field directly (rather than re-serializing [`AuditLog`]) so
+/// [`verify_audit_chain`] can recompute the same hash from the columns
+/// read back out of Postgres, without needing to reconstruct `EventType`/
+/// `Action`/`AuditResult` enum values.
+fn canonical_entry_json(
+    timestamp: DateTime<Utc>,
+    event_type: &str,
+    actor: &str,
+    action: &str,
+    resource: &str,
+    result: &str,
+    ip_address: Option<&str>,
+    details: Option<&str>,
+) -> String {
+    serde_json::json!({
+        "timestamp": timestamp.to_rfc3339(),
+        "event_type": event_type,
+        "actor": actor,
+        "action": action,
+        "resource": resource,
+        "result": result,
+        "ip_address": ip_address,
+        "details": details,
+    })
+    .to_string()
+}
+
+fn chain_hash(canonical_json: &str, prev_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(canonical_json.as_bytes());
+    hasher.update(prev_hash.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Serializes an enum field (`EventType`/`Action`/`AuditResult`) the same
+/// way `#[serde(rename_all = "SCREAMING_SNAKE_CASE")]` would, without
+/// hand-maintaining a second `match` per enum.
+fn enum_as_str<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => "UNKNOWN".to_string(),
+    }
+}
+
 impl AuditLog {
     /// Create a new audit log entry
     pub fn new(
@@ -81,8 +164,58 @@ impl AuditLog {
         self
     }
 
-    /// Write audit log entry to the audit log target
-    pub fn write(self) {
+    /// Persists this entry to the append-only `audit_log` table, extending
+    /// `chain`'s hash, and emits it to the `audit` log target as a
+    /// secondary sink.
+    ///
+    /// A row always carries forward `chain`'s tip even if the `INSERT`
+    /// fails - a DB outage shouldn't also corrupt the in-memory chain for
+    /// the next write - so a persistence failure is logged loudly rather
+    /// than silently dropped.
+    pub async fn write(self, chain: &AuditChain, pool: &PgPool) {
+        let event_type_str = enum_as_str(&self.event_type);
+        let action_str = enum_as_str(&self.action);
+        let result_str = enum_as_str(&self.result);
+
+        let canonical_json = canonical_entry_json(
+            self.timestamp,
+            &event_type_str,
+            &self.actor,
+            &action_str,
+            &self.resource,
+            &result_str,
+            self.ip_address.as_deref(),
+            self.details.as_deref(),
+        );
+
+        {
+            let mut tip = chain.tip.lock().await;
+            let entry_hash = chain_hash(&canonical_json, &tip);
+
+            let insert = sqlx::query!(
+                "INSERT INTO audit_log
+                    (recorded_at, event_type, actor, action, resource, result, ip_address, details, prev_hash, entry_hash)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                self.timestamp,
+                event_type_str,
+                self.actor,
+                action_str,
+                self.resource,
+                result_str,
+                self.ip_address,
+                self.details,
+                *tip,
+                entry_hash,
+            )
+            .execute(pool)
+            .await;
+
+            match insert {
+                Ok(_) => *tip = entry_hash,
+                Err(e) => log::error!("Failed to persist audit log entry (chain tip left unchanged): {}", e),
+            }
+        }
+
         match serde_json::to_string(&self) {
             Ok(json) => log::info!(target: "audit", "{}", json),
             Err(e) => log::error!("Failed to serialize audit log: {}", e),
@@ -90,9 +223,89 @@ impl AuditLog {
     }
 }
 
-/// Extract actor from API key in request
-/// Returns a hash of the API key for privacy
+/// Result of walking `audit_log` end to end and recomputing every row's
+/// hash - returned by [`verify_audit_chain`] and served by
+/// `GET /api/shared/audit/verify`.
+#[derive(Debug, Serialize)]
+pub struct AuditChainVerification {
+    pub valid: bool,
+    pub rows_checked: i64,
+    pub broken_at_id: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// Walks `audit_log` in `id` order, recomputing each row's `entry_hash`
+/// from its own columns and the previous row's `entry_hash`, and reports
+/// the first row where either doesn't match what's stored - evidence that
+/// row (or an earlier one) was edited or deleted out from under the chain.
+pub async fn verify_audit_chain(pool: &PgPool) -> Result<AuditChainVerification, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT id, recorded_at, event_type, actor, action, resource, result, ip_address, details, prev_hash, entry_hash
+         FROM audit_log ORDER BY id ASC"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut rows_checked: i64 = 0;
+
+    for row in &rows {
+        if row.prev_hash != expected_prev {
+            return Ok(AuditChainVerification {
+                valid: false,
+                rows_checked,
+                broken_at_id: Some(row.id),
+                reason: Some(format!(
+                    "row {} has prev_hash that doesn't match the preceding row's entry_hash",
+                    row.id
+                )),
+            });
+        }
+
+        let canonical_json = canonical_entry_json(
+            row.recorded_at,
+            &row.event_type,
+            &row.actor,
+            &row.action,
+            &row.resource,
+            &row.result,
+            row.ip_address.as_deref(),
+            row.details.as_deref(),
+        );
+        let recomputed = chain_hash(&canonical_json, &row.prev_hash);
+
+        if recomputed != row.entry_hash {
+            return Ok(AuditChainVerification {
+                valid: false,
+                rows_checked,
+                broken_at_id: Some(row.id),
+                reason: Some(format!("row {} entry_hash doesn't match its recomputed hash", row.id)),
+            });
+        }
+
+        expected_prev = row.entry_hash.clone();
+        rows_checked += 1;
+    }
+
+    Ok(AuditChainVerification {
+        valid: true,
+        rows_checked,
+        broken_at_id: None,
+        reason: None,
+    })
+}
+
+/// Extract actor from the request, preferring the `keyId` a verified
+/// `HttpSignatureAuth` pass attributed the request to over the legacy
+/// `X-API-Key` hash, so a `SharedApiAccess` entry names exactly which
+/// partner system made the call instead of an opaque key fingerprint.
 pub fn extract_actor_from_request(req: &ServiceRequest) -> String {
+    use crate::middleware::http_signature::SignatureActor;
+
+    if let Some(actor) = req.extensions().get::<SignatureActor>() {
+        return actor.0.clone();
+    }
+
     req.headers()
         .get("X-API-Key")
         .and_then(|h| h.to_str().ok())
@@ -159,4 +372,20 @@ mod tests {
         let json = serde_json::to_string(&log).unwrap();
         assert!(json.contains("Invalid flag value"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_chain_hash_changes_with_prev_hash() {
+        let json = canonical_entry_json(
+            Utc::now(),
+            "FLAG_UPDATE",
+            "actor",
+            "UPDATE",
+            "suspect:hash",
+            "SUCCESS",
+            None,
+            None,
+        );
+
+        assert_ne!(chain_hash(&json, GENESIS_HASH), chain_hash(&json, "different_prev_hash"));
+    }
+}
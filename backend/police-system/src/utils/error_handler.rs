@@ -0,0 +1,165 @@
+use actix_web::HttpResponse;
+use actix_web::error::InternalError;
+use actix_web::http::StatusCode;
+use uuid::Uuid;
+use serde_json::json;
+use validator::ValidationErrors;
+
+/// Generate a unique correlation ID for error tracking
+pub fn generate_correlation_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Handle database errors with logging and safe error response
+///
+/// A unique-constraint violation (e.g. a duplicate `personal_id` on
+/// `create_suspect`) is mapped to 409 Conflict instead of a generic 500, and
+/// a missing row is mapped to 404 - both are client errors, not server
+/// failures, and shouldn't be logged or reported as one.
+pub fn handle_database_error(error: sqlx::Error, context: &str) -> HttpResponse {
+    let correlation_id = generate_correlation_id();
+
+    if let sqlx::Error::Database(db_err) = &error {
+        if db_err.is_unique_violation() {
+            log::warn!(
+                "Unique constraint violation [{}] in {}: {}",
+                correlation_id,
+                context,
+                error
+            );
+
+            return HttpResponse::Conflict().json(json!({
+                "error": "A record with that personal ID already exists",
+                "correlation_id": correlation_id
+            }));
+        }
+    }
+
+    if matches!(error, sqlx::Error::RowNotFound) {
+        return handle_not_found("resource", &correlation_id);
+    }
+
+    // Log full error details server-side
+    log::error!(
+        "Database error [{}] in {}: {}",
+        correlation_id,
+        context,
+        error
+    );
+
+    // Return generic error to client
+    HttpResponse::InternalServerError().json(json!({
+        "error": "Service temporarily unavailable",
+        "correlation_id": correlation_id
+    }))
+}
+
+/// Handle not found errors
+pub fn handle_not_found(resource: &str, identifier: &str) -> HttpResponse {
+    let correlation_id = generate_correlation_id();
+
+    log::warn!(
+        "Resource not found [{}]: {} with identifier: {}",
+        correlation_id,
+        resource,
+        identifier
+    );
+
+    HttpResponse::NotFound().json(json!({
+        "error": "Resource not found",
+        "correlation_id": correlation_id
+    }))
+}
+
+/// Handle validation errors with safe error information
+pub fn handle_validation_error(message: &str, context: &str) -> HttpResponse {
+    let correlation_id = generate_correlation_id();
+
+    log::warn!(
+        "Validation error [{}] in {}: {}",
+        correlation_id,
+        context,
+        message
+    );
+
+    HttpResponse::BadRequest().json(json!({
+        "error": "Invalid request format",
+        "correlation_id": correlation_id
+    }))
+}
+
+/// Handle `validator` failures with field-level details
+///
+/// Used by [`crate::utils::validation::ValidatedJson`] so a malformed
+/// `personal_id` or blank `full_name` is rejected at the extractor level
+/// with a response that tells the caller exactly which field failed,
+/// rather than the generic `handle_validation_error` message.
+pub fn handle_validation_error_detailed(
+    errors: &ValidationErrors,
+    context: &str,
+) -> InternalError<String> {
+    let correlation_id = generate_correlation_id();
+
+    log::warn!(
+        "Validation error [{}] in {}: {}",
+        correlation_id,
+        context,
+        errors
+    );
+
+    let fields: serde_json::Map<String, serde_json::Value> = errors
+        .field_errors()
+        .iter()
+        .map(|(field, field_errors)| {
+            let messages: Vec<String> = field_errors
+                .iter()
+                .map(|e| {
+                    e.message
+                        .clone()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| e.code.to_string())
+                })
+                .collect();
+            (field.to_string(), json!(messages))
+        })
+        .collect();
+
+    let response = HttpResponse::BadRequest().json(json!({
+        "error": "Invalid request format",
+        "fields": fields,
+        "correlation_id": correlation_id
+    }));
+
+    InternalError::from_response("validation failed".to_string(), response)
+        .use_status_code(StatusCode::BAD_REQUEST)
+}
+
+/// Handle unauthorized access attempts
+pub fn handle_unauthorized(context: &str) -> HttpResponse {
+    let correlation_id = generate_correlation_id();
+
+    log::warn!(
+        "Unauthorized access attempt [{}] in {}",
+        correlation_id,
+        context
+    );
+
+    HttpResponse::Unauthorized().json(json!({
+        "error": "Authentication required",
+        "correlation_id": correlation_id
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_correlation_id() {
+        let id1 = generate_correlation_id();
+        let id2 = generate_correlation_id();
+
+        assert_ne!(id1, id2);
+        assert!(Uuid::parse_str(&id1).is_ok());
+    }
+}
@@ -1,28 +1,74 @@
-use sha2::{Sha256, Digest};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::env;
 
-/// Generate a hash of personal_id for logging purposes
-/// 
-/// Creates a SHA-256 hash of the personal ID and returns the first 16 hex characters.
-/// This allows correlation of log entries without exposing actual PII.
-/// 
+type HmacSha256 = Hmac<Sha256>;
+
+/// Minimum length, in bytes, required of `LOG_HASH_PEPPER` - short enough to
+/// reject an obviously weak value, not an attempt at a real entropy check.
+const MIN_PEPPER_LEN: usize = 32;
+
+/// Per-deployment secret pepper used to key [`hash_for_logging`].
+///
+/// Swedish personal numbers (a date plus four digits) have well under 2^30
+/// possible values, so a plain `Sha256` digest is brute-forceable from a
+/// precomputed table by anyone with log access. Keying the hash with a
+/// secret pepper that never leaves this process makes that attack
+/// infeasible without also compromising the pepper itself.
+#[derive(Clone)]
+pub struct LoggingPepper(Vec<u8>);
+
+impl LoggingPepper {
+    /// Loads the pepper from `LOG_HASH_PEPPER`, failing fast at boot if it's
+    /// missing or too short rather than letting a weak/absent pepper
+    /// silently degrade log pseudonymization.
+    pub fn from_env() -> Result<Self, String> {
+        let pepper = env::var("LOG_HASH_PEPPER")
+            .map_err(|_| "LOG_HASH_PEPPER must be set".to_string())?;
+
+        Self::new(pepper.into_bytes())
+    }
+
+    /// Constructs a pepper directly - used by tests to supply a fixed value
+    /// instead of relying on the environment.
+    pub fn new(pepper: Vec<u8>) -> Result<Self, String> {
+        if pepper.len() < MIN_PEPPER_LEN {
+            return Err(format!(
+                "LOG_HASH_PEPPER must be at least {} bytes long",
+                MIN_PEPPER_LEN
+            ));
+        }
+
+        Ok(Self(pepper))
+    }
+}
+
+/// Generate a keyed hash of personal_id for logging purposes
+///
+/// Computes `HMAC-SHA256(pepper, personal_id)` and returns the first 16 hex
+/// characters, so log correlation stays deterministic while reversing the
+/// digest requires the secret pepper rather than just a brute-force table.
+///
 /// # Arguments
-/// 
+///
 /// * `personal_id` - The Swedish personal ID to hash
-/// 
+/// * `pepper` - The per-deployment secret pepper keying the HMAC
+///
 /// # Returns
-/// 
-/// * `String` - First 16 characters of SHA-256 hash in hexadecimal
-/// 
+///
+/// * `String` - First 16 characters of the HMAC-SHA256 digest in hexadecimal
+///
 /// # Example
-/// 
+///
 /// ```
-/// log::info!("Processing request for personal_id hash: {}", 
-///     hash_for_logging(&personal_id));
+/// log::info!("Processing request for personal_id hash: {}",
+///     hash_for_logging(&personal_id, &pepper));
 /// ```
-pub fn hash_for_logging(personal_id: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(personal_id.as_bytes());
-    let result = hasher.finalize();
+pub fn hash_for_logging(personal_id: &str, pepper: &LoggingPepper) -> String {
+    let mut mac = HmacSha256::new_from_slice(&pepper.0)
+        .expect("HMAC can be keyed with any length, including the enforced minimum");
+    mac.update(personal_id.as_bytes());
+    let result = mac.finalize().into_bytes();
     format!("{:x}", result)[..16].to_string()
 }
 
@@ -30,29 +76,49 @@ pub fn hash_for_logging(personal_id: &str) -> String {
 mod tests {
     use super::*;
 
+    fn test_pepper() -> LoggingPepper {
+        LoggingPepper::new(vec![b'p'; MIN_PEPPER_LEN]).unwrap()
+    }
+
     #[test]
     fn test_hash_for_logging() {
+        let pepper = test_pepper();
         let pid = "19850312-2398";
-        let hash = hash_for_logging(pid);
-        
+        let hash = hash_for_logging(pid, &pepper);
+
         // Should return 16 character hex string
         assert_eq!(hash.len(), 16);
         assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
-        
+
         // Same input should produce same hash
-        assert_eq!(hash, hash_for_logging(pid));
-        
+        assert_eq!(hash, hash_for_logging(pid, &pepper));
+
         // Different input should produce different hash
-        let different_hash = hash_for_logging("19900101-1234");
+        let different_hash = hash_for_logging("19900101-1234", &pepper);
         assert_ne!(hash, different_hash);
     }
 
     #[test]
     fn test_hash_consistency() {
+        let pepper = test_pepper();
         let pid = "19850312-2398";
-        let hash1 = hash_for_logging(pid);
-        let hash2 = hash_for_logging(pid);
-        
+        let hash1 = hash_for_logging(pid, &pepper);
+        let hash2 = hash_for_logging(pid, &pepper);
+
         assert_eq!(hash1, hash2, "Hash should be deterministic");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_different_pepper_changes_hash() {
+        let pid = "19850312-2398";
+        let pepper_a = LoggingPepper::new(vec![b'a'; MIN_PEPPER_LEN]).unwrap();
+        let pepper_b = LoggingPepper::new(vec![b'b'; MIN_PEPPER_LEN]).unwrap();
+
+        assert_ne!(hash_for_logging(pid, &pepper_a), hash_for_logging(pid, &pepper_b));
+    }
+
+    #[test]
+    fn test_pepper_too_short_is_rejected() {
+        assert!(LoggingPepper::new(vec![b'p'; MIN_PEPPER_LEN - 1]).is_err());
+    }
+}
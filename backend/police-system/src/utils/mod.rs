@@ -0,0 +1,10 @@
+pub mod audit;
+pub mod logging;
+pub mod error_handler;
+pub mod api_error;
+pub mod pagination;
+pub mod validation;
+
+pub use pagination::PageParams;
+pub use validation::ValidatedJson;
+pub use api_error::ApiError;
@@ -0,0 +1,45 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::utils::error_handler::handle_validation_error_detailed;
+
+/// A drop-in replacement for `web::Json<T>` that also runs `T::validate()`
+/// before the handler ever sees the payload.
+///
+/// Without this, every handler that takes a `CreateSuspect`/`UpdateSuspect`
+/// body has to remember to call `.validate()` itself (and some didn't) -
+/// `ValidatedJson<T>` makes that impossible to forget, since extraction
+/// itself fails with a `400` and field-level error details on an invalid
+/// payload.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> ValidatedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> FromRequest for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json_fut = web::Json::<T>::from_request(req, payload);
+
+        Box::pin(async move {
+            let json = json_fut.await?;
+            let value = json.into_inner();
+
+            if let Err(errors) = value.validate() {
+                return Err(handle_validation_error_detailed(&errors, "request_body").into());
+            }
+
+            Ok(ValidatedJson(value))
+        })
+    }
+}